@@ -13,6 +13,19 @@ pub struct Model {
     pub ch_pl_id: i64,
     pub ch_role_mention_id: Option<i64>,
     pub ch_mention_flag: bool,
+    pub ch_show_replies: bool,
+    pub ch_show_reposts: bool,
+    pub ch_primed: bool,
+    pub ch_backfill: i32,
+    pub ch_embed: bool,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub ch_template: Option<String>,
+    pub ch_on_removal: String,
+    pub ch_interval: Option<i32>,
+    pub ch_gu_id: Option<i64>,
+    pub ch_enabled: bool,
+    pub ch_crosspost: bool,
+    pub ch_delivery: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -25,8 +38,32 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Platforms,
+    #[sea_orm(has_many = "super::filters::Entity")]
+    Filters,
+    #[sea_orm(
+        belongs_to = "super::guilds::Entity",
+        from = "Column::ChGuId",
+        to = "super::guilds::Column::GuId",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    Guilds,
     #[sea_orm(has_many = "super::posts::Entity")]
     Posts,
+    #[sea_orm(has_many = "super::video_kinds::Entity")]
+    VideoKinds,
+}
+
+impl Related<super::filters::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Filters.def()
+    }
+}
+
+impl Related<super::guilds::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Guilds.def()
+    }
 }
 
 impl Related<super::platforms::Entity> for Entity {
@@ -41,4 +78,10 @@ impl Related<super::posts::Entity> for Entity {
     }
 }
 
+impl Related<super::video_kinds::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::VideoKinds.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
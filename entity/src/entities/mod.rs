@@ -3,5 +3,8 @@
 pub mod prelude;
 
 pub mod channels;
+pub mod filters;
+pub mod guilds;
 pub mod platforms;
 pub mod posts;
+pub mod video_kinds;
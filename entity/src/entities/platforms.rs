@@ -16,6 +16,8 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::channels::Entity")]
     Channels,
+    #[sea_orm(has_many = "super::posts::Entity")]
+    Posts,
 }
 
 impl Related<super::channels::Entity> for Entity {
@@ -24,4 +26,10 @@ impl Related<super::channels::Entity> for Entity {
     }
 }
 
+impl Related<super::posts::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Posts.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -7,10 +7,16 @@ use sea_orm::entity::prelude::*;
 pub struct Model {
     #[sea_orm(primary_key)]
     pub po_id: i64,
-    #[sea_orm(unique)]
     pub po_name: String,
     pub po_time_added: DateTime,
     pub po_ch_id: i64,
+    pub po_pl_id: i64,
+    pub po_message_id: Option<i64>,
+    pub po_state: Option<String>,
+    pub po_removed: bool,
+    pub po_discord_channel_id: Option<i64>,
+    pub po_publish_pending: bool,
+    pub po_delivery: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -23,6 +29,14 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Channels,
+    #[sea_orm(
+        belongs_to = "super::platforms::Entity",
+        from = "Column::PoPlId",
+        to = "super::platforms::Column::PlId",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Platforms,
 }
 
 impl Related<super::channels::Entity> for Entity {
@@ -31,4 +45,10 @@ impl Related<super::channels::Entity> for Entity {
     }
 }
 
+impl Related<super::platforms::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Platforms.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -1,5 +1,8 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.10.7
 
 pub use super::channels::Entity as Channels;
+pub use super::filters::Entity as Filters;
+pub use super::guilds::Entity as Guilds;
 pub use super::platforms::Entity as Platforms;
 pub use super::posts::Entity as Posts;
+pub use super::video_kinds::Entity as VideoKinds;
@@ -2,6 +2,22 @@ pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
 mod m20230203_140020_optional_role_ping;
+mod m20261017_120000_widen_source_ids;
+mod m20261017_130000_reply_repost_flags;
+mod m20261017_140000_scoped_post_dedup;
+mod m20261017_150000_channel_priming;
+mod m20261017_160000_embed_announcements;
+mod m20261017_170000_announcement_templates;
+mod m20261017_180000_post_filters;
+mod m20261017_190000_video_kinds;
+mod m20261017_200000_announcement_messages;
+mod m20261017_210000_removed_posts;
+mod m20261017_220000_link_intervals;
+mod m20261017_230000_guild_settings;
+mod m20261017_240000_paused_links;
+mod m20261017_250000_announcement_channels;
+mod m20261017_260000_crossposting;
+mod m20261017_270000_delivery_methods;
 
 pub struct Migrator;
 
@@ -11,6 +27,22 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20220101_000001_create_table::Migration),
             Box::new(m20230203_140020_optional_role_ping::Migration),
+            Box::new(m20261017_120000_widen_source_ids::Migration),
+            Box::new(m20261017_130000_reply_repost_flags::Migration),
+            Box::new(m20261017_140000_scoped_post_dedup::Migration),
+            Box::new(m20261017_150000_channel_priming::Migration),
+            Box::new(m20261017_160000_embed_announcements::Migration),
+            Box::new(m20261017_170000_announcement_templates::Migration),
+            Box::new(m20261017_180000_post_filters::Migration),
+            Box::new(m20261017_190000_video_kinds::Migration),
+            Box::new(m20261017_200000_announcement_messages::Migration),
+            Box::new(m20261017_210000_removed_posts::Migration),
+            Box::new(m20261017_220000_link_intervals::Migration),
+            Box::new(m20261017_230000_guild_settings::Migration),
+            Box::new(m20261017_240000_paused_links::Migration),
+            Box::new(m20261017_250000_announcement_channels::Migration),
+            Box::new(m20261017_260000_crossposting::Migration),
+            Box::new(m20261017_270000_delivery_methods::Migration),
         ]
     }
 }
@@ -179,6 +179,30 @@ pub enum Channels {
     MentionFlag,
     #[iden = "ch_role_mention_id"]
     RoleMentionId,
+    #[iden = "ch_show_replies"]
+    ShowReplies,
+    #[iden = "ch_show_reposts"]
+    ShowReposts,
+    #[iden = "ch_primed"]
+    Primed,
+    #[iden = "ch_backfill"]
+    Backfill,
+    #[iden = "ch_embed"]
+    Embed,
+    #[iden = "ch_template"]
+    Template,
+    #[iden = "ch_on_removal"]
+    OnRemoval,
+    #[iden = "ch_interval"]
+    Interval,
+    #[iden = "ch_gu_id"]
+    GuildId,
+    #[iden = "ch_enabled"]
+    Enabled,
+    #[iden = "ch_crosspost"]
+    Crosspost,
+    #[iden = "ch_delivery"]
+    Delivery,
 }
 
 #[derive(Iden)]
@@ -193,4 +217,18 @@ pub enum Posts {
     TimeAdded,
     #[iden = "po_ch_id"]
     ChannelId,
+    #[iden = "po_pl_id"]
+    PlatformId,
+    #[iden = "po_message_id"]
+    MessageId,
+    #[iden = "po_state"]
+    State,
+    #[iden = "po_removed"]
+    Removed,
+    #[iden = "po_discord_channel_id"]
+    DiscordChannelId,
+    #[iden = "po_publish_pending"]
+    PublishPending,
+    #[iden = "po_delivery"]
+    Delivery,
 }
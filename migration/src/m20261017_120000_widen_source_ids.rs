@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20220101_000001_create_table::{Channels, Posts};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Channels::Table)
+                    .modify_column(ColumnDef::new(Channels::Name).string_len(512).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .modify_column(ColumnDef::new(Posts::Name).string_len(512).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .modify_column(ColumnDef::new(Posts::Name).string_len(32).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Channels::Table)
+                    .modify_column(ColumnDef::new(Channels::Name).string_len(48).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
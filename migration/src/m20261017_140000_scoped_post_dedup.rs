@@ -0,0 +1,127 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, Statement};
+
+use crate::m20220101_000001_create_table::{Platforms, Posts};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+async fn execute_sql(manager: &SchemaManager<'_>, sql: &str) -> Result<(), DbErr> {
+    let stmt = Statement::from_string(manager.get_database_backend(), sql.to_owned());
+    manager.get_connection().execute(stmt).await?;
+
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Posts::PlatformId).big_integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        execute_sql(
+            manager,
+            "UPDATE posts SET po_pl_id = channels.ch_pl_id \
+             FROM channels WHERE posts.po_ch_id = channels.ch_id",
+        )
+        .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .modify_column(ColumnDef::new(Posts::PlatformId).big_integer().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .from(Posts::Table, Posts::PlatformId)
+                    .to(Platforms::Table, Platforms::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        execute_sql(
+            manager,
+            "ALTER TABLE posts DROP CONSTRAINT posts_po_name_key",
+        )
+        .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-posts-platform-name-channel")
+                    .unique()
+                    .table(Posts::Table)
+                    .col(Posts::PlatformId)
+                    .col(Posts::Name)
+                    .col(Posts::ChannelId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Deduplication used to be global, so only the first link following a source
+        // recorded its posts. Give every other link the same history, otherwise they
+        // would announce the whole current feed after the upgrade.
+        execute_sql(
+            manager,
+            "INSERT INTO posts (po_name, po_time_added, po_ch_id, po_pl_id) \
+             SELECT posts.po_name, posts.po_time_added, other.ch_id, other.ch_pl_id \
+             FROM posts \
+             JOIN channels AS owner ON posts.po_ch_id = owner.ch_id \
+             JOIN channels AS other ON other.ch_pl_id = owner.ch_pl_id \
+                 AND other.ch_name = owner.ch_name \
+                 AND other.ch_id <> owner.ch_id \
+             ON CONFLICT DO NOTHING",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-posts-platform-name-channel")
+                    .table(Posts::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Keep the oldest record of each post so the global unique key can be restored
+        execute_sql(
+            manager,
+            "DELETE FROM posts AS newer USING posts AS older \
+             WHERE newer.po_name = older.po_name AND newer.po_id > older.po_id",
+        )
+        .await?;
+
+        execute_sql(
+            manager,
+            "ALTER TABLE posts ADD CONSTRAINT posts_po_name_key UNIQUE (po_name)",
+        )
+        .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .drop_column(Posts::PlatformId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20220101_000001_create_table::Channels;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Filters::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Filters::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Filters::ChannelId).big_integer().not_null())
+                    .col(ColumnDef::new(Filters::Kind).string_len(16).not_null())
+                    .col(ColumnDef::new(Filters::Value).string_len(256).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .from(Filters::Table, Filters::ChannelId)
+                    .to(Channels::Table, Channels::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .unique()
+                    .name("idx-filters-channel-kind-value")
+                    .table(Filters::Table)
+                    .col(Filters::ChannelId)
+                    .col(Filters::Kind)
+                    .col(Filters::Value)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Filters::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum Filters {
+    #[iden = "filters"]
+    Table,
+    #[iden = "fi_id"]
+    Id,
+    #[iden = "fi_ch_id"]
+    ChannelId,
+    #[iden = "fi_kind"]
+    Kind,
+    #[iden = "fi_value"]
+    Value,
+}
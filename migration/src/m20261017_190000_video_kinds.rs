@@ -0,0 +1,89 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20220101_000001_create_table::Channels;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(VideoKinds::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(VideoKinds::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(VideoKinds::ChannelId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(VideoKinds::Kind).string_len(16).not_null())
+                    .col(
+                        ColumnDef::new(VideoKinds::Enabled)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(ColumnDef::new(VideoKinds::MentionFlag).boolean().null())
+                    .col(ColumnDef::new(VideoKinds::Template).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .from(VideoKinds::Table, VideoKinds::ChannelId)
+                    .to(Channels::Table, Channels::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .unique()
+                    .name("idx-video_kinds-channel-kind")
+                    .table(VideoKinds::Table)
+                    .col(VideoKinds::ChannelId)
+                    .col(VideoKinds::Kind)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(VideoKinds::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum VideoKinds {
+    #[iden = "video_kinds"]
+    Table,
+    #[iden = "vk_id"]
+    Id,
+    #[iden = "vk_ch_id"]
+    ChannelId,
+    #[iden = "vk_kind"]
+    Kind,
+    #[iden = "vk_enabled"]
+    Enabled,
+    #[iden = "vk_mention_flag"]
+    MentionFlag,
+    #[iden = "vk_template"]
+    Template,
+}
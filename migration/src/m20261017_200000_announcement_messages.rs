@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20220101_000001_create_table::Posts;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Posts::MessageId).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Posts::State).string_len(16).null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .drop_column(Posts::State)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .drop_column(Posts::MessageId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20220101_000001_create_table::{Channels, Posts};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Channels::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Channels::OnRemoval)
+                            .string_len(8)
+                            .not_null()
+                            .default("ignore"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Posts::Removed)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .drop_column(Posts::Removed)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Channels::Table)
+                    .drop_column(Channels::OnRemoval)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
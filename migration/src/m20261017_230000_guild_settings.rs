@@ -0,0 +1,95 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20220101_000001_create_table::Channels;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Guilds::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Guilds::Id)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Guilds::LinkLimit).integer().null())
+                    .col(ColumnDef::new(Guilds::ChannelLinkLimit).integer().null())
+                    .col(ColumnDef::new(Guilds::RoleMentionId).big_integer().null())
+                    .col(ColumnDef::new(Guilds::Template).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Channels::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Channels::GuildId).big_integer().null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk-channels-guild")
+                    .from(Channels::Table, Channels::GuildId)
+                    .to(Guilds::Table, Guilds::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-channels-guild")
+                    .table(Channels::Table)
+                    .col(Channels::GuildId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Channels::Table)
+                    .drop_column(Channels::GuildId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Guilds::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+pub enum Guilds {
+    #[iden = "guilds"]
+    Table,
+    #[iden = "gu_id"]
+    Id,
+    #[iden = "gu_link_limit"]
+    LinkLimit,
+    #[iden = "gu_channel_link_limit"]
+    ChannelLinkLimit,
+    #[iden = "gu_role_mention_id"]
+    RoleMentionId,
+    #[iden = "gu_template"]
+    Template,
+}
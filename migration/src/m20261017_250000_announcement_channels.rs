@@ -0,0 +1,33 @@
+use sea_orm_migration::prelude::*;
+
+use crate::m20220101_000001_create_table::Posts;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(Posts::DiscordChannelId).big_integer().null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posts::Table)
+                    .drop_column(Posts::DiscordChannelId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
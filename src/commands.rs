@@ -1,18 +1,35 @@
+use crate::post_checker::filter::FilterKind;
+use crate::post_checker::{
+    registry, template, youtube_uploads, DeliveryMethod, Platform, Post, RemovalAction, VideoKind,
+    PLATFORMS,
+};
 use crate::sp;
 use crate::Data;
-use entity::{channels, platforms};
-use migration::OnConflict;
+use entity::{channels, filters, guilds, platforms, posts, video_kinds};
+use migration::{Expr, OnConflict};
 use poise::serenity_prelude::Mentionable;
 use poise::serenity_prelude::Role;
 use poise::serenity_prelude::RoleId;
-use sea_orm::ActiveValue::NotSet;
+use sea_orm::ActiveModelTrait;
+use sea_orm::DatabaseConnection;
 use sea_orm::ModelTrait;
 use sea_orm::PaginatorTrait;
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, Set};
+use std::fmt;
+use std::str::FromStr;
 
 type Error = Box<dyn std::error::Error + Send + Sync>;
 type Context<'a> = poise::Context<'a, Data, Error>;
 
+/// Width of `channels.ch_description`.
+const DESCRIPTION_LEN: usize = 64;
+
+/// Links a Discord channel may have unless its guild's limit was raised.
+const CHANNEL_LINK_LIMIT: u64 = 12;
+
+/// Links a guild may have across all its channels unless its limit was raised.
+const GUILD_LINK_LIMIT: u64 = 50;
+
 #[poise::command(slash_command, prefix_command)]
 pub(crate) async fn account_age(
     ctx: Context<'_>,
@@ -28,35 +45,137 @@ pub(crate) async fn account_age(
     Ok(())
 }
 
-#[derive(poise::ChoiceParameter)]
-pub enum PlatformType {
-    #[name = "YouTube"]
-    YouTube,
-    #[name = "Reddit"]
-    Reddit,
-}
+/// A slash command choice over the registered platforms.
+#[derive(Clone, Copy)]
+pub struct PlatformType(&'static Platform);
 
 impl PlatformType {
-    fn str_repr(&self) -> &'static str {
-        match *self {
-            Self::YouTube => "YouTube",
-            Self::Reddit => "Reddit",
-        }
+    pub fn name(&self) -> &'static str {
+        self.0.name
+    }
+
+    fn platform(&self) -> &'static Platform {
+        self.0
+    }
+}
+
+#[poise::async_trait]
+impl poise::SlashArgument for PlatformType {
+    async fn extract(
+        _: &sp::Context,
+        _: poise::ApplicationCommandOrAutocompleteInteraction<'_>,
+        value: &sp::json::Value,
+    ) -> Result<Self, poise::SlashArgError> {
+        let choice_key = value
+            .as_u64()
+            .ok_or(poise::SlashArgError::CommandStructureMismatch(
+                "expected u64",
+            ))?;
+
+        PLATFORMS.get(choice_key as usize).map(Self).ok_or(
+            poise::SlashArgError::CommandStructureMismatch("out of bounds choice key"),
+        )
+    }
+
+    fn create(builder: &mut sp::CreateApplicationCommandOption) {
+        builder.kind(sp::CommandOptionType::Integer);
+    }
+
+    fn choices() -> Vec<poise::CommandParameterChoice> {
+        PLATFORMS
+            .iter()
+            .map(|platform| poise::CommandParameterChoice {
+                name: platform.name.to_owned(),
+                localizations: Default::default(),
+            })
+            .collect()
     }
 }
 
+impl FromStr for PlatformType {
+    type Err = poise::InvalidChoice;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Platform::find(s).map(Self).ok_or(poise::InvalidChoice)
+    }
+}
+
+impl fmt::Display for PlatformType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 #[poise::command(slash_command, prefix_command, required_permissions = "ADMINISTRATOR")]
 pub(crate) async fn add_channel(
     ctx: Context<'_>,
     #[description = "Platform"] platform: PlatformType,
     #[description = "Channel ID"] channel_id: String,
-    #[description = "Channel name"] channel_name: String,
+    #[description = "Channel name"] channel_name: Option<String>,
     #[description = "Should ping"] should_ping: Option<bool>,
     #[description = "Mentioned role"] mention_role: Option<Role>,
+    #[description = "Announce replies"] show_replies: Option<bool>,
+    #[description = "Announce reposts and boosts"] show_reposts: Option<bool>,
+    #[description = "Number of recent posts to announce right away"]
+    #[min = 0]
+    #[max = 25]
+    backfill: Option<u8>,
+    #[description = "Announce with a rich embed"] embed: Option<bool>,
+    #[description = "Handling of removed posts"] on_removal: Option<RemovalAction>,
+    #[description = "Minutes between checks, defaults to the platform's interval"]
+    #[min = 1]
+    #[max = 1440]
+    interval: Option<u16>,
+    #[description = "Discord channel to announce in, defaults to this one"] destination: Option<
+        sp::GuildChannel,
+    >,
 ) -> Result<(), Error> {
     let db = ctx.framework().user_data.database.clone();
 
-    let platform_name = platform.str_repr();
+    let platform_name = platform.name();
+
+    let destination = match destination {
+        Some(destination) => Some(destination),
+        None => ctx.channel_id().to_channel(ctx).await?.guild(),
+    };
+
+    if let Some(destination) = &destination {
+        if let Some(response) =
+            check_destination(ctx, destination, DeliveryMethod::Bot, false).await?
+        {
+            ctx.say(response).await?;
+            return Ok(());
+        }
+    }
+
+    let target = destination.map_or_else(|| ctx.channel_id(), |destination| destination.id);
+
+    let Some(checker) = ctx
+        .framework()
+        .user_data
+        .registry
+        .checker(platform.platform())
+    else {
+        let response = "No such platform.";
+        ctx.say(response).await?;
+        return Ok(());
+    };
+
+    let source = match checker.validate_id(&channel_id).await {
+        Ok(source) => source,
+        Err(response) => {
+            ctx.say(response).await?;
+            return Ok(());
+        }
+    };
+
+    let channel_name = channel_name
+        .or(source.name)
+        .unwrap_or_else(|| source.id.clone())
+        .chars()
+        .take(DESCRIPTION_LEN)
+        .collect::<String>();
 
     let platform_info = platforms::Entity::find()
         .filter(platforms::Column::PlName.eq(platform_name))
@@ -71,42 +190,89 @@ pub(crate) async fn add_channel(
 
     let platform_info = platform_info.unwrap();
 
-    let cnt = platform_info
-        .find_related(channels::Entity)
-        .count(&db)
-        .await?;
+    let guild = guild_settings(ctx).await?;
 
-    const LIMIT: u64 = 12;
+    // Updating an existing link doesn't take up another slot
+    if find_link(ctx, target, platform, source.id.clone())
+        .await?
+        .is_none()
+    {
+        let limit = guild
+            .as_ref()
+            .and_then(|guild| guild.gu_channel_link_limit)
+            .map_or(CHANNEL_LINK_LIMIT, |limit| limit as u64);
 
-    if cnt >= LIMIT {
-        let response =
-            format!("Too many linked channels in this Discord channel (limit: {LIMIT}).");
-        ctx.say(response).await?;
-        return Ok(());
+        let cnt = channels::Entity::find()
+            .filter(channels::Column::ChDiscordChannelId.eq(target.0 as i64))
+            .count(&db)
+            .await?;
+
+        if cnt >= limit {
+            let response = format!(
+                "Too many linked channels in {} (limit: {limit}).",
+                target.mention()
+            );
+            ctx.say(response).await?;
+            return Ok(());
+        }
+
+        if let Some(guild) = &guild {
+            let limit = guild
+                .gu_link_limit
+                .map_or(GUILD_LINK_LIMIT, |limit| limit as u64);
+
+            let cnt = guild.find_related(channels::Entity).count(&db).await?;
+
+            if cnt >= limit {
+                let response = format!("Too many linked channels in this server (limit: {limit}).");
+                ctx.say(response).await?;
+                return Ok(());
+            }
+        }
     }
 
     let channel = channels::ActiveModel {
-        ch_name: Set(channel_id),
+        ch_name: Set(source.id),
         ch_description: Set(channel_name.clone()),
         ch_pl_id: Set(platform_info.pl_id),
-        ch_discord_channel_id: Set(ctx.channel_id().into()),
+        ch_discord_channel_id: Set(target.into()),
         ch_mention_flag: Set(should_ping.unwrap_or(true)),
-        ch_role_mention_id: mention_role
-            .map_or_else(|| NotSet, |ref role| Set(Some(role.id.0 as i64))),
+        ch_role_mention_id: Set(mention_role.as_ref().map(|role| role.id.0 as i64)),
+        ch_show_replies: Set(show_replies.unwrap_or(false)),
+        ch_show_reposts: Set(show_reposts.unwrap_or(false)),
+        ch_primed: Set(false),
+        ch_backfill: Set(backfill.unwrap_or(0).into()),
+        ch_embed: Set(embed.unwrap_or(false)),
+        ch_on_removal: Set(on_removal.unwrap_or_default().as_str().to_owned()),
+        ch_interval: Set(interval.map(|minutes| i32::from(minutes) * 60)),
+        ch_gu_id: Set(guild.map(|guild| guild.gu_id)),
         ..Default::default()
     };
 
+    let mut updated = vec![
+        channels::Column::ChDescription,
+        channels::Column::ChMentionFlag,
+        channels::Column::ChShowReplies,
+        channels::Column::ChShowReposts,
+        channels::Column::ChBackfill,
+        channels::Column::ChEmbed,
+        channels::Column::ChOnRemoval,
+        channels::Column::ChInterval,
+        channels::Column::ChGuId,
+    ];
+
+    // Re-adding a link without a role keeps its role, links without one use the server's default
+    if mention_role.is_some() {
+        updated.push(channels::Column::ChRoleMentionId);
+    }
+
     channels::Entity::insert(channel)
         .on_conflict(
             OnConflict::columns([
                 channels::Column::ChName,
                 channels::Column::ChDiscordChannelId,
             ])
-            .update_columns([
-                channels::Column::ChDescription,
-                channels::Column::ChMentionFlag,
-                channels::Column::ChRoleMentionId,
-            ])
+            .update_columns(updated)
             .to_owned(),
         )
         .exec(&db)
@@ -115,7 +281,7 @@ pub(crate) async fn add_channel(
     ctx.send(|f| {
         f.content(format!(
             "Channel configuration updated: **{channel_name}** -> **{}**.",
-            ctx.channel_id().mention()
+            target.mention()
         ))
         .allowed_mentions(|m| m.empty_parse())
     })
@@ -127,13 +293,26 @@ pub(crate) async fn add_channel(
 pub(crate) async fn list_channels(
     ctx: Context<'_>,
     #[description = "Platform"] platform: Option<PlatformType>,
+    #[description = "Discord channel to list the links of, defaults to this one"]
+    destination: Option<sp::GuildChannel>,
 ) -> Result<(), Error> {
     let db = ctx.framework().user_data.database.clone();
 
-    let platform_name = platform.as_ref().map(PlatformType::str_repr);
+    if destination
+        .as_ref()
+        .is_some_and(|destination| Some(destination.guild_id) != ctx.guild_id())
+    {
+        let response = "That channel isn't in this server.";
+        ctx.say(response).await?;
+        return Ok(());
+    }
+
+    let target = destination.map_or_else(|| ctx.channel_id(), |destination| destination.id);
+
+    let platform_name = platform.as_ref().map(PlatformType::name);
 
-    let sel_base = channels::Entity::find()
-        .filter(channels::Column::ChDiscordChannelId.eq(ctx.channel_id().0 as i64));
+    let sel_base =
+        channels::Entity::find().filter(channels::Column::ChDiscordChannelId.eq(target.0 as i64));
 
     let sel = if let Some(platform_name_str) = platform_name {
         sel_base
@@ -160,23 +339,28 @@ pub(crate) async fn list_channels(
         "".to_owned()
     };
 
+    let default_role = guild_settings(ctx)
+        .await?
+        .and_then(|guild| guild.gu_role_mention_id);
+
     ctx.send(|f| {
         f.embed(|e| {
             e.title("Linked Channels")
                 .description(format!(
                     "The list of active channel links in {}{filter}.",
-                    ctx.channel_id().mention()
+                    target.mention()
                 ))
                 .colour((149, 66, 245))
                 .fields(sel.into_iter().map(|ch| {
                     let info = format!(
-                        "**ID:** {}\n**Mentions:** {}\n**Pings:** {}",
+                        "**ID:** {}\n**Mentions:** {}\n**Pings:** {}\n**Paused:** {}",
                         ch.ch_name,
-                        ch.ch_role_mention_id.map_or_else(
+                        ch.ch_role_mention_id.or(default_role).map_or_else(
                             || "@everyone".to_owned(),
                             |v| RoleId(v as u64).mention().to_string()
                         ),
-                        if ch.ch_mention_flag { "Yes" } else { "No" }
+                        if ch.ch_mention_flag { "Yes" } else { "No" },
+                        if ch.ch_enabled { "No" } else { "Yes" }
                     );
 
                     (ch.ch_description, info, true)
@@ -196,21 +380,12 @@ pub(crate) async fn remove_channel(
 ) -> Result<(), Error> {
     let db = ctx.framework().user_data.database.clone();
 
-    let sel_base = channels::Entity::find()
-        .filter(
-            channels::Column::ChDiscordChannelId
-                .eq(ctx.channel_id().0 as i64)
-                .and(channels::Column::ChName.eq(channel_id)),
-        )
-        .find_also_related(platforms::Entity)
-        .filter(platforms::Column::PlName.eq(platform.str_repr()))
-        .one(&db)
-        .await?;
-
-    let Some((channel, _)) = sel_base else {
-        let response = "No such channel found.";
-        ctx.say(response).await?;
-        return Ok(());
+    let channel = match find_channel(ctx, platform, channel_id).await? {
+        Ok(channel) => channel,
+        Err(response) => {
+            ctx.say(response).await?;
+            return Ok(());
+        }
     };
 
     let name = channel.ch_description.clone();
@@ -222,3 +397,1198 @@ pub(crate) async fn remove_channel(
 
     Ok(())
 }
+
+/// Stops announcing a link's posts while keeping its settings and history.
+#[poise::command(slash_command, prefix_command, required_permissions = "ADMINISTRATOR")]
+pub(crate) async fn pause_channel(
+    ctx: Context<'_>,
+    #[description = "Platform"] platform: PlatformType,
+    #[description = "Channel ID"] channel_id: String,
+) -> Result<(), Error> {
+    let db = ctx.framework().user_data.database.clone();
+
+    let channel = match find_channel(ctx, platform, channel_id).await? {
+        Ok(channel) => channel,
+        Err(response) => {
+            ctx.say(response).await?;
+            return Ok(());
+        }
+    };
+
+    if !channel.ch_enabled {
+        let response = format!("**{}** is already paused.", channel.ch_description);
+        ctx.say(response).await?;
+        return Ok(());
+    }
+
+    channels::ActiveModel {
+        ch_id: Set(channel.ch_id),
+        ch_enabled: Set(false),
+        ..Default::default()
+    }
+    .update(&db)
+    .await?;
+
+    ctx.say(format!("**{}** paused.", channel.ch_description))
+        .await?;
+    Ok(())
+}
+
+/// Announces a paused link's posts again, skipping the ones made while it was paused.
+#[poise::command(slash_command, prefix_command, required_permissions = "ADMINISTRATOR")]
+pub(crate) async fn resume_channel(
+    ctx: Context<'_>,
+    #[description = "Platform"] platform: PlatformType,
+    #[description = "Channel ID"] channel_id: String,
+) -> Result<(), Error> {
+    let db = ctx.framework().user_data.database.clone();
+
+    let channel = match find_channel(ctx, platform, channel_id).await? {
+        Ok(channel) => channel,
+        Err(response) => {
+            ctx.say(response).await?;
+            return Ok(());
+        }
+    };
+
+    if channel.ch_enabled {
+        let response = format!("**{}** isn't paused.", channel.ch_description);
+        ctx.say(response).await?;
+        return Ok(());
+    }
+
+    // Priming again records the backlog as seen before anything is announced
+    channels::ActiveModel {
+        ch_id: Set(channel.ch_id),
+        ch_enabled: Set(true),
+        ch_primed: Set(false),
+        ..Default::default()
+    }
+    .update(&db)
+    .await?;
+
+    ctx.say(format!("**{}** resumed.", channel.ch_description))
+        .await?;
+    Ok(())
+}
+
+/// Changes the settings of an existing link in place, keeping its history.
+#[allow(clippy::too_many_arguments)]
+#[poise::command(slash_command, prefix_command, required_permissions = "ADMINISTRATOR")]
+pub(crate) async fn edit_channel(
+    ctx: Context<'_>,
+    #[description = "Platform"] platform: PlatformType,
+    #[description = "Channel ID"] channel_id: String,
+    #[description = "Channel name"] channel_name: Option<String>,
+    #[description = "Should ping"] should_ping: Option<bool>,
+    #[description = "Mentioned role"] mention_role: Option<Role>,
+    #[description = "Mention the server's default role instead of the link's own"]
+    clear_role: Option<bool>,
+    #[description = "Discord channel to announce in from now on"] destination: Option<
+        sp::GuildChannel,
+    >,
+    #[description = "Publish announcements to following servers"] crosspost: Option<bool>,
+    #[description = "How announcements are sent"] delivery: Option<DeliveryMethod>,
+) -> Result<(), Error> {
+    let db = ctx.framework().user_data.database.clone();
+
+    let channel = match find_channel(ctx, platform, channel_id).await? {
+        Ok(channel) => channel,
+        Err(response) => {
+            ctx.say(response).await?;
+            return Ok(());
+        }
+    };
+
+    let clear_role = clear_role.unwrap_or(false);
+
+    if clear_role && mention_role.is_some() {
+        let response = "Either set a role or clear it, not both.";
+        ctx.say(response).await?;
+        return Ok(());
+    }
+
+    let current = sp::ChannelId(channel.ch_discord_channel_id as u64);
+    let destination = destination.filter(|destination| destination.id != current);
+    let method = delivery.unwrap_or_else(|| DeliveryMethod::from_column(&channel.ch_delivery));
+
+    let target = match &destination {
+        Some(destination) => Some(destination.clone()),
+        None if crosspost == Some(true) || delivery.is_some() => {
+            current.to_channel(ctx).await?.guild()
+        }
+        None => None,
+    };
+
+    // Only messages in announcement channels reach following servers
+    if crosspost == Some(true)
+        && target
+            .as_ref()
+            .is_none_or(|target| target.kind != sp::ChannelType::News)
+    {
+        let response = "Only announcements in announcement channels can be published.";
+        ctx.say(response).await?;
+        return Ok(());
+    }
+
+    let publish = crosspost.unwrap_or(channel.ch_crosspost);
+
+    match (&destination, &target) {
+        (Some(destination), _) => {
+            if let Some(response) = move_link(ctx, &channel, destination, method, publish).await? {
+                ctx.say(response).await?;
+                return Ok(());
+            }
+        }
+        (None, Some(target)) if delivery.is_some() || crosspost == Some(true) => {
+            if let Some(response) = check_destination(ctx, target, method, publish).await? {
+                ctx.say(response).await?;
+                return Ok(());
+            }
+        }
+        _ => {}
+    }
+
+    let channel_name = channel_name
+        .map(|name| name.chars().take(DESCRIPTION_LEN).collect::<String>())
+        .unwrap_or(channel.ch_description);
+
+    let mut model = channels::ActiveModel {
+        ch_id: Set(channel.ch_id),
+        ch_description: Set(channel_name.clone()),
+        ..Default::default()
+    };
+
+    if let Some(should_ping) = should_ping {
+        model.ch_mention_flag = Set(should_ping);
+    }
+
+    if let Some(role) = mention_role {
+        model.ch_role_mention_id = Set(Some(role.id.0 as i64));
+    }
+
+    // Links without a role of their own mention the server's default role, or @everyone
+    if clear_role {
+        model.ch_role_mention_id = Set(None);
+    }
+
+    if let Some(crosspost) = crosspost {
+        model.ch_crosspost = Set(crosspost);
+    }
+
+    if let Some(delivery) = delivery {
+        model.ch_delivery = Set(delivery.as_str().to_owned());
+    }
+
+    model.update(&db).await?;
+
+    ctx.send(|f| {
+        f.content(format!(
+            "Channel configuration updated: **{channel_name}** -> **{}**.",
+            destination
+                .map_or(current, |destination| destination.id)
+                .mention()
+        ))
+        .allowed_mentions(|m| m.empty_parse())
+    })
+    .await?;
+    Ok(())
+}
+
+/// Moves a link and its announcement history to another Discord channel.
+#[poise::command(slash_command, prefix_command, required_permissions = "ADMINISTRATOR")]
+pub(crate) async fn move_channel(
+    ctx: Context<'_>,
+    #[description = "Platform"] platform: PlatformType,
+    #[description = "Channel ID"] channel_id: String,
+    #[description = "Discord channel to announce in from now on"] destination: sp::GuildChannel,
+) -> Result<(), Error> {
+    let channel = match find_channel(ctx, platform, channel_id).await? {
+        Ok(channel) => channel,
+        Err(response) => {
+            ctx.say(response).await?;
+            return Ok(());
+        }
+    };
+
+    let method = DeliveryMethod::from_column(&channel.ch_delivery);
+
+    if let Some(response) =
+        move_link(ctx, &channel, &destination, method, channel.ch_crosspost).await?
+    {
+        ctx.say(response).await?;
+        return Ok(());
+    }
+
+    let unpublished = if channel.ch_crosspost && destination.kind != sp::ChannelType::News {
+        ", announcements are no longer published"
+    } else {
+        ""
+    };
+
+    ctx.send(|f| {
+        f.content(format!(
+            "**{}** moved to {}{unpublished}.",
+            channel.ch_description,
+            destination.id.mention()
+        ))
+        .allowed_mentions(|m| m.empty_parse())
+    })
+    .await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, prefix_command, required_permissions = "ADMINISTRATOR")]
+pub(crate) async fn set_template(
+    ctx: Context<'_>,
+    #[description = "Platform"] platform: PlatformType,
+    #[description = "Channel ID"] channel_id: String,
+    #[description = "Announcement template, leave empty to restore the default"] template: Option<
+        String,
+    >,
+) -> Result<(), Error> {
+    let db = ctx.framework().user_data.database.clone();
+
+    if let Some(Err(response)) = template.as_deref().map(template::validate) {
+        ctx.say(response).await?;
+        return Ok(());
+    }
+
+    let channel = match find_channel(ctx, platform, channel_id).await? {
+        Ok(channel) => channel,
+        Err(response) => {
+            ctx.say(response).await?;
+            return Ok(());
+        }
+    };
+
+    let name = channel.ch_description.clone();
+    let reset = template.is_none();
+
+    channels::ActiveModel {
+        ch_id: Set(channel.ch_id),
+        ch_template: Set(template),
+        ..Default::default()
+    }
+    .update(&db)
+    .await?;
+
+    let response = if reset {
+        format!("Announcements for **{name}** use the default message again.")
+    } else {
+        format!("Announcement template for **{name}** updated.")
+    };
+
+    ctx.say(response).await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, prefix_command, required_permissions = "ADMINISTRATOR")]
+pub(crate) async fn preview_template(
+    ctx: Context<'_>,
+    #[description = "Platform"] platform: PlatformType,
+    #[description = "Channel ID"] channel_id: String,
+    #[description = "Template to try out, defaults to the saved one"] template: Option<String>,
+) -> Result<(), Error> {
+    if let Some(Err(response)) = template.as_deref().map(template::validate) {
+        ctx.say(response).await?;
+        return Ok(());
+    }
+
+    let channel = match find_channel(ctx, platform, channel_id).await? {
+        Ok(channel) => channel,
+        Err(response) => {
+            ctx.say(response).await?;
+            return Ok(());
+        }
+    };
+
+    let mut channel = ctx
+        .framework()
+        .user_data
+        .registry
+        .with_guild_defaults(&channel)
+        .await?;
+
+    let Some(checker) = ctx
+        .framework()
+        .user_data
+        .registry
+        .checker(platform.platform())
+    else {
+        let response = "No such platform.";
+        ctx.say(response).await?;
+        return Ok(());
+    };
+
+    // Fetching the latest post can take longer than Discord waits for a response
+    ctx.defer().await?;
+
+    let latest = checker
+        .fetch(&channel.ch_name)
+        .await
+        .ok()
+        .and_then(|posts| posts.into_iter().next());
+
+    let post = match latest {
+        Some(mut post) => {
+            checker.enrich(&mut post).await.ok();
+            post
+        }
+        None => Post {
+            url: "https://example.com/".to_owned(),
+            title: Some("Example post".to_owned()),
+            text: Some("This is what a new post would look like.".to_owned()),
+            ..Default::default()
+        },
+    };
+
+    if template.is_some() {
+        channel.ch_template = template;
+    }
+
+    let text = registry::content(
+        checker.as_ref(),
+        &channel,
+        &post,
+        &registry::mention(&channel),
+    );
+
+    // Previews never ping, whatever the link's mention settings are
+    ctx.send(|f| {
+        if channel.ch_embed {
+            f.embed(|e| {
+                checker.embed(&channel, &post, e);
+                e
+            });
+        }
+
+        f.content(text).allowed_mentions(|m| m.empty_parse())
+    })
+    .await?;
+    Ok(())
+}
+
+/// Manages the rules deciding which posts of a link get announced.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "ADMINISTRATOR",
+    subcommands("filter_add", "filter_list", "filter_remove")
+)]
+pub(crate) async fn filter(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "ADMINISTRATOR",
+    rename = "add"
+)]
+pub(crate) async fn filter_add(
+    ctx: Context<'_>,
+    #[description = "Platform"] platform: PlatformType,
+    #[description = "Channel ID"] channel_id: String,
+    #[description = "Rule"] kind: FilterKind,
+    #[description = "Title pattern, flair, username or score"] value: String,
+) -> Result<(), Error> {
+    let db = ctx.framework().user_data.database.clone();
+
+    let value = match kind.validate(&value) {
+        Ok(value) => value,
+        Err(response) => {
+            ctx.say(response).await?;
+            return Ok(());
+        }
+    };
+
+    let channel = match find_channel(ctx, platform, channel_id).await? {
+        Ok(channel) => channel,
+        Err(response) => {
+            ctx.say(response).await?;
+            return Ok(());
+        }
+    };
+
+    let cnt = channel.find_related(filters::Entity).count(&db).await?;
+
+    const LIMIT: u64 = 25;
+
+    if cnt >= LIMIT {
+        let response = format!("Too many filters on this channel (limit: {LIMIT}).");
+        ctx.say(response).await?;
+        return Ok(());
+    }
+
+    let filter = filters::ActiveModel {
+        fi_ch_id: Set(channel.ch_id),
+        fi_kind: Set(kind.as_str().to_owned()),
+        fi_value: Set(value.clone()),
+        ..Default::default()
+    };
+
+    filters::Entity::insert(filter)
+        .on_conflict(
+            OnConflict::columns([
+                filters::Column::FiChId,
+                filters::Column::FiKind,
+                filters::Column::FiValue,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(&db)
+        .await?;
+
+    ctx.send(|f| {
+        f.content(format!(
+            "Filter added to **{}**: {kind} `{value}`.",
+            channel.ch_description
+        ))
+        .allowed_mentions(|m| m.empty_parse())
+    })
+    .await?;
+    Ok(())
+}
+
+#[poise::command(slash_command, prefix_command, rename = "list")]
+pub(crate) async fn filter_list(
+    ctx: Context<'_>,
+    #[description = "Platform"] platform: PlatformType,
+    #[description = "Channel ID"] channel_id: String,
+) -> Result<(), Error> {
+    let db = ctx.framework().user_data.database.clone();
+
+    let channel = match find_channel(ctx, platform, channel_id).await? {
+        Ok(channel) => channel,
+        Err(response) => {
+            ctx.say(response).await?;
+            return Ok(());
+        }
+    };
+
+    let rules = channel.find_related(filters::Entity).all(&db).await?;
+
+    if rules.is_empty() {
+        let response = format!(
+            "No filters set, every post of **{}** is announced.",
+            channel.ch_description
+        );
+        ctx.say(response).await?;
+        return Ok(());
+    }
+
+    let list = rules
+        .into_iter()
+        .map(|rule| {
+            let kind = FilterKind::from_column(&rule.fi_kind)
+                .map_or(rule.fi_kind, |kind| kind.to_string());
+
+            format!("**{}:** {kind} `{}`", rule.fi_id, rule.fi_value)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    ctx.send(|f| {
+        f.embed(|e| {
+            e.title("Filters")
+                .description(format!(
+                    "Filters of **{}**, posts need to match one of the title, flair or \
+                     author rules when any are set.\n\n{list}",
+                    channel.ch_description
+                ))
+                .colour((149, 66, 245))
+        })
+        .allowed_mentions(|m| m.empty_parse())
+    })
+    .await?;
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    prefix_command,
+    required_permissions = "ADMINISTRATOR",
+    rename = "remove"
+)]
+pub(crate) async fn filter_remove(
+    ctx: Context<'_>,
+    #[description = "Platform"] platform: PlatformType,
+    #[description = "Channel ID"] channel_id: String,
+    #[description = "Filter number, as shown by /filter list"] filter_id: i64,
+) -> Result<(), Error> {
+    let db = ctx.framework().user_data.database.clone();
+
+    let channel = match find_channel(ctx, platform, channel_id).await? {
+        Ok(channel) => channel,
+        Err(response) => {
+            ctx.say(response).await?;
+            return Ok(());
+        }
+    };
+
+    let res = filters::Entity::delete_many()
+        .filter(filters::Column::FiId.eq(filter_id))
+        .filter(filters::Column::FiChId.eq(channel.ch_id))
+        .exec(&db)
+        .await?;
+
+    let response = if res.rows_affected == 0 {
+        "No such filter found.".to_owned()
+    } else {
+        format!("Filter removed from **{}**.", channel.ch_description)
+    };
+
+    ctx.say(response).await?;
+    Ok(())
+}
+
+/// Whether a video type pings, `default` follows the link's own choice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum VideoPing {
+    #[name = "yes"]
+    Yes,
+    #[name = "no"]
+    No,
+    #[name = "default"]
+    Default,
+}
+
+/// Sets which types of YouTube videos a link announces, and how.
+#[poise::command(slash_command, prefix_command, required_permissions = "ADMINISTRATOR")]
+pub(crate) async fn video_type(
+    ctx: Context<'_>,
+    #[description = "Channel ID"] channel_id: String,
+    #[description = "Video type"] kind: VideoKind,
+    #[description = "Announce this type"] announce: Option<bool>,
+    #[description = "Ping for this type, \"default\" uses the link's choice"] ping: Option<
+        VideoPing,
+    >,
+    #[description = "Template for this type, \"default\" uses the link's template"]
+    template: Option<String>,
+) -> Result<(), Error> {
+    let db = ctx.framework().user_data.database.clone();
+
+    let template = match template.as_deref().map(str::trim) {
+        Some("default") => Some(None),
+        Some(template) => match template::validate(template) {
+            Ok(()) => Some(Some(template.to_owned())),
+            Err(response) => {
+                ctx.say(response).await?;
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+
+    let youtube = PlatformType(&youtube_uploads::PLATFORM);
+
+    let channel = match find_channel(ctx, youtube, channel_id).await? {
+        Ok(channel) => channel,
+        Err(response) => {
+            ctx.say(response).await?;
+            return Ok(());
+        }
+    };
+
+    let existing = channel
+        .find_related(video_kinds::Entity)
+        .filter(video_kinds::Column::VkKind.eq(kind.as_str()))
+        .one(&db)
+        .await?;
+
+    let mut settings = match existing {
+        Some(settings) => settings.into(),
+        None => video_kinds::ActiveModel {
+            vk_ch_id: Set(channel.ch_id),
+            vk_kind: Set(kind.as_str().to_owned()),
+            vk_enabled: Set(true),
+            vk_mention_flag: Set(None),
+            vk_template: Set(None),
+            ..Default::default()
+        },
+    };
+
+    if let Some(announce) = announce {
+        settings.vk_enabled = Set(announce);
+    }
+
+    if let Some(ping) = ping {
+        settings.vk_mention_flag = Set(match ping {
+            VideoPing::Yes => Some(true),
+            VideoPing::No => Some(false),
+            VideoPing::Default => None,
+        });
+    }
+
+    if let Some(template) = template {
+        settings.vk_template = Set(template);
+    }
+
+    settings.save(&db).await?;
+
+    let all = channel.find_related(video_kinds::Entity).all(&db).await?;
+
+    let summary = VideoKind::ALL
+        .into_iter()
+        .map(|kind| {
+            let settings = all.iter().find(|s| s.vk_kind == kind.as_str());
+
+            let announced = settings.is_none_or(|s| s.vk_enabled);
+            let pinged = settings
+                .and_then(|s| s.vk_mention_flag)
+                .unwrap_or(channel.ch_mention_flag);
+            let template = settings.is_some_and(|s| s.vk_template.is_some());
+
+            format!(
+                "**{kind}:** {}, {}{}",
+                if announced {
+                    "announced"
+                } else {
+                    "not announced"
+                },
+                if pinged { "pings" } else { "no pings" },
+                if template { ", own template" } else { "" }
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    ctx.send(|f| {
+        f.content(format!(
+            "Video types of **{}** updated.\n{summary}",
+            channel.ch_description
+        ))
+        .allowed_mentions(|m| m.empty_parse())
+    })
+    .await?;
+    Ok(())
+}
+
+/// Shows the state of every platform, such as the YouTube Data API quota.
+#[poise::command(slash_command, prefix_command)]
+pub(crate) async fn status(ctx: Context<'_>) -> Result<(), Error> {
+    let data = ctx.framework().user_data;
+
+    ctx.send(|f| {
+        f.embed(|e| {
+            e.title("Status")
+                .description(&data.version)
+                .colour((149, 66, 245))
+                .fields(PLATFORMS.iter().map(|platform| {
+                    let status = data
+                        .registry
+                        .checker(platform)
+                        .and_then(|checker| checker.status())
+                        .unwrap_or_else(|| "Running.".to_owned());
+
+                    (platform.name, status, false)
+                }))
+        })
+    })
+    .await?;
+    Ok(())
+}
+
+/// Manages the defaults and limits of this server.
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    subcommands("guild_show", "guild_role", "guild_template")
+)]
+pub(crate) async fn guild(_: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+#[poise::command(slash_command, prefix_command, guild_only, rename = "show")]
+pub(crate) async fn guild_show(ctx: Context<'_>) -> Result<(), Error> {
+    let db = ctx.framework().user_data.database.clone();
+
+    let Some(guild) = guild_settings(ctx).await? else {
+        return Ok(());
+    };
+
+    let links = guild.find_related(channels::Entity).count(&db).await?;
+    let link_limit = guild
+        .gu_link_limit
+        .map_or(GUILD_LINK_LIMIT, |limit| limit as u64);
+    let channel_limit = guild
+        .gu_channel_link_limit
+        .map_or(CHANNEL_LINK_LIMIT, |limit| limit as u64);
+
+    ctx.send(|f| {
+        f.embed(|e| {
+            e.title("Server Settings")
+                .colour((149, 66, 245))
+                .field("Links", format!("{links} of {link_limit}"), true)
+                .field("Links per channel", channel_limit.to_string(), true)
+                .field(
+                    "Default role",
+                    guild.gu_role_mention_id.map_or_else(
+                        || "@everyone".to_owned(),
+                        |v| RoleId(v as u64).mention().to_string(),
+                    ),
+                    true,
+                )
+                .field(
+                    "Default template",
+                    guild
+                        .gu_template
+                        .as_deref()
+                        .map_or_else(|| "Not set".to_owned(), |t| format!("```\n{t}\n```")),
+                    false,
+                )
+        })
+        .allowed_mentions(|m| m.empty_parse())
+    })
+    .await?;
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    rename = "role"
+)]
+pub(crate) async fn guild_role(
+    ctx: Context<'_>,
+    #[description = "Role links without their own role mention, leave empty for @everyone"]
+    role: Option<Role>,
+) -> Result<(), Error> {
+    let db = ctx.framework().user_data.database.clone();
+
+    let Some(guild) = guild_settings(ctx).await? else {
+        return Ok(());
+    };
+
+    let response = match &role {
+        Some(role) => format!("Links mention {} by default.", role.mention()),
+        None => "Links mention @everyone by default.".to_owned(),
+    };
+
+    guilds::ActiveModel {
+        gu_id: Set(guild.gu_id),
+        gu_role_mention_id: Set(role.map(|role| role.id.0 as i64)),
+        ..Default::default()
+    }
+    .update(&db)
+    .await?;
+
+    ctx.send(|f| f.content(response).allowed_mentions(|m| m.empty_parse()))
+        .await?;
+    Ok(())
+}
+
+#[poise::command(
+    slash_command,
+    prefix_command,
+    guild_only,
+    required_permissions = "ADMINISTRATOR",
+    rename = "template"
+)]
+pub(crate) async fn guild_template(
+    ctx: Context<'_>,
+    #[description = "Template for links without one, leave empty to clear"] template: Option<
+        String,
+    >,
+) -> Result<(), Error> {
+    let db = ctx.framework().user_data.database.clone();
+
+    if let Some(Err(response)) = template.as_deref().map(template::validate) {
+        ctx.say(response).await?;
+        return Ok(());
+    }
+
+    let Some(guild) = guild_settings(ctx).await? else {
+        return Ok(());
+    };
+
+    let reset = template.is_none();
+
+    guilds::ActiveModel {
+        gu_id: Set(guild.gu_id),
+        gu_template: Set(template),
+        ..Default::default()
+    }
+    .update(&db)
+    .await?;
+
+    let response = if reset {
+        "Links without a template use the default message again."
+    } else {
+        "Default announcement template updated."
+    };
+
+    ctx.say(response).await?;
+    Ok(())
+}
+
+/// Raises or restores the link limits of a server, for bot owners only.
+#[poise::command(slash_command, prefix_command, owners_only, hide_in_help)]
+pub(crate) async fn guild_limits(
+    ctx: Context<'_>,
+    #[description = "Server ID"] guild_id: String,
+    #[description = "Links in the server, leave empty for the default"]
+    #[min = 1]
+    #[max = 10000]
+    link_limit: Option<u16>,
+    #[description = "Links per channel, leave empty for the default"]
+    #[min = 1]
+    #[max = 1000]
+    channel_limit: Option<u16>,
+) -> Result<(), Error> {
+    let db = ctx.framework().user_data.database.clone();
+
+    let Ok(guild_id) = guild_id.trim().parse::<u64>() else {
+        ctx.say(format!("**{guild_id}** is not a server ID."))
+            .await?;
+        return Ok(());
+    };
+
+    let guild = guilds::ActiveModel {
+        gu_id: Set(guild_id as i64),
+        gu_link_limit: Set(link_limit.map(i32::from)),
+        gu_channel_link_limit: Set(channel_limit.map(i32::from)),
+        ..Default::default()
+    };
+
+    guilds::Entity::insert(guild)
+        .on_conflict(
+            OnConflict::column(guilds::Column::GuId)
+                .update_columns([
+                    guilds::Column::GuLinkLimit,
+                    guilds::Column::GuChannelLinkLimit,
+                ])
+                .to_owned(),
+        )
+        .exec_without_returning(&db)
+        .await?;
+
+    ctx.say(format!(
+        "Limits of server **{guild_id}**: {} links, {} per channel.",
+        link_limit.map_or(GUILD_LINK_LIMIT, u64::from),
+        channel_limit.map_or(CHANNEL_LINK_LIMIT, u64::from)
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Loads the settings of the guild the command was used in, `None` outside of guilds.
+async fn guild_settings(ctx: Context<'_>) -> Result<Option<guilds::Model>, Error> {
+    let db = &ctx.framework().user_data.database;
+
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(None);
+    };
+
+    let guild_id = guild_id.0 as i64;
+
+    if let Some(guild) = guilds::Entity::find_by_id(guild_id).one(db).await? {
+        return Ok(Some(guild));
+    }
+
+    // Guilds are registered as the bot joins them, this only covers a missed event
+    insert_guild(db, guild_id).await?;
+
+    Ok(guilds::Entity::find_by_id(guild_id).one(db).await?)
+}
+
+/// Registers a guild the bot is in, called for every guild on start and when joining one.
+///
+/// Links created before guilds were tracked are assigned to the guild on the way.
+pub(crate) async fn register_guild(
+    db: &DatabaseConnection,
+    guild: &sp::Guild,
+) -> Result<(), Error> {
+    let guild_id = guild.id.0 as i64;
+
+    insert_guild(db, guild_id).await?;
+
+    // Links can target threads, which the cache keeps apart from the guild's channels
+    let channel_ids = guild
+        .channels
+        .keys()
+        .copied()
+        .chain(guild.threads.iter().map(|thread| thread.id))
+        .map(|id| id.0 as i64)
+        .collect::<Vec<_>>();
+
+    channels::Entity::update_many()
+        .col_expr(channels::Column::ChGuId, Expr::value(guild_id))
+        .filter(channels::Column::ChGuId.is_null())
+        .filter(channels::Column::ChDiscordChannelId.is_in(channel_ids))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+async fn insert_guild(db: &DatabaseConnection, guild_id: i64) -> Result<(), Error> {
+    guilds::Entity::insert(guilds::ActiveModel {
+        gu_id: Set(guild_id),
+        ..Default::default()
+    })
+    .on_conflict(
+        OnConflict::column(guilds::Column::GuId)
+            .do_nothing()
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Looks up a link in the channel the command was used in, or elsewhere in the guild.
+///
+/// Explains why when there is no single such link.
+async fn find_channel(
+    ctx: Context<'_>,
+    platform: PlatformType,
+    channel_id: String,
+) -> Result<Result<channels::Model, String>, Error> {
+    let db = &ctx.framework().user_data.database;
+
+    if let Some(channel) = find_link(ctx, ctx.channel_id(), platform, channel_id.clone()).await? {
+        return Ok(Ok(channel));
+    }
+
+    let not_found = "No such channel found.".to_owned();
+
+    let Some(guild) = guild_settings(ctx).await? else {
+        return Ok(Err(not_found));
+    };
+
+    let mut links = guild
+        .find_related(channels::Entity)
+        .filter(channels::Column::ChName.eq(channel_id))
+        .find_also_related(platforms::Entity)
+        .filter(platforms::Column::PlName.eq(platform.name()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|(channel, _)| channel)
+        .collect::<Vec<_>>();
+
+    let response = match links.len() {
+        0 => not_found,
+        1 => return Ok(Ok(links.remove(0))),
+        _ => format!(
+            "**{}** is linked in {}, use the command in the channel to change.",
+            links[0].ch_description,
+            links
+                .iter()
+                .map(|link| sp::ChannelId(link.ch_discord_channel_id as u64)
+                    .mention()
+                    .to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+
+    Ok(Err(response))
+}
+
+/// Looks up a link announcing in `discord_channel`.
+async fn find_link(
+    ctx: Context<'_>,
+    discord_channel: sp::ChannelId,
+    platform: PlatformType,
+    channel_id: String,
+) -> Result<Option<channels::Model>, Error> {
+    let db = &ctx.framework().user_data.database;
+
+    let channel = channels::Entity::find()
+        .filter(
+            channels::Column::ChDiscordChannelId
+                .eq(discord_channel.0 as i64)
+                .and(channels::Column::ChName.eq(channel_id)),
+        )
+        .find_also_related(platforms::Entity)
+        .filter(platforms::Column::PlName.eq(platform.name()))
+        .one(db)
+        .await?
+        .map(|(channel, _)| channel);
+
+    Ok(channel)
+}
+
+/// Explains why links can't announce in `destination` through `delivery`, `None` if they can.
+///
+/// With `crosspost`, the announcements must also be publishable to following servers.
+async fn check_destination(
+    ctx: Context<'_>,
+    destination: &sp::GuildChannel,
+    delivery: DeliveryMethod,
+    crosspost: bool,
+) -> Result<Option<String>, Error> {
+    let discord = ctx.serenity_context();
+
+    if Some(destination.guild_id) != ctx.guild_id() {
+        return Ok(Some(
+            "Links can only announce in channels of this server.".to_owned(),
+        ));
+    }
+
+    let thread = matches!(
+        destination.kind,
+        sp::ChannelType::NewsThread
+            | sp::ChannelType::PublicThread
+            | sp::ChannelType::PrivateThread
+    );
+
+    let forum = destination.kind == sp::ChannelType::Forum;
+
+    if !thread && !forum && !destination.is_text_based() {
+        return Ok(Some(format!(
+            "{} isn't a text, announcement, forum or thread channel.",
+            destination.id.mention()
+        )));
+    }
+
+    if delivery == DeliveryMethod::Webhook && (thread || forum) {
+        return Ok(Some(
+            "Webhooks can't announce in threads or forum channels.".to_owned(),
+        ));
+    }
+
+    // Threads take their permission overwrites from the channel they were started in
+    let overwrites = match destination.parent_id.filter(|_| thread) {
+        Some(parent) => parent.to_channel(discord).await?.guild(),
+        None => None,
+    };
+    let overwrites = overwrites.as_ref().unwrap_or(destination);
+
+    let member = destination
+        .guild_id
+        .member(discord, discord.cache.current_user_id())
+        .await?;
+
+    let permissions = match discord.cache.guild(destination.guild_id) {
+        Some(guild) => guild.user_permissions_in(overwrites, &member)?,
+        None => destination
+            .guild_id
+            .to_partial_guild(discord)
+            .await?
+            .user_permissions_in(overwrites, &member)?,
+    };
+
+    let send = if thread {
+        sp::Permissions::SEND_MESSAGES_IN_THREADS
+    } else {
+        sp::Permissions::SEND_MESSAGES
+    };
+
+    if !permissions.contains(sp::Permissions::VIEW_CHANNEL | send | sp::Permissions::EMBED_LINKS) {
+        return Ok(Some(format!(
+            "I need the View Channel, Send Messages and Embed Links permissions in {}.",
+            destination.id.mention()
+        )));
+    }
+
+    if delivery == DeliveryMethod::Webhook
+        && !permissions.contains(sp::Permissions::MANAGE_WEBHOOKS)
+    {
+        return Ok(Some(format!(
+            "I need the Manage Webhooks permission in {} to announce through a webhook.",
+            destination.id.mention()
+        )));
+    }
+
+    // Publishing a message the bot didn't send itself, such as a webhook's, takes Manage Messages
+    if crosspost && !permissions.contains(sp::Permissions::MANAGE_MESSAGES) {
+        return Ok(Some(format!(
+            "I need the Manage Messages permission in {} to publish announcements.",
+            destination.id.mention()
+        )));
+    }
+
+    Ok(None)
+}
+
+/// Points a link and the announcements it already sent at `destination`.
+///
+/// Publishing stops unless `destination` is an announcement channel. Explains why the link
+/// can't be moved instead, `None` once it was.
+async fn move_link(
+    ctx: Context<'_>,
+    channel: &channels::Model,
+    destination: &sp::GuildChannel,
+    delivery: DeliveryMethod,
+    crosspost: bool,
+) -> Result<Option<String>, Error> {
+    let db = &ctx.framework().user_data.database;
+
+    let crosspost = crosspost && destination.kind == sp::ChannelType::News;
+
+    if destination.id.0 as i64 == channel.ch_discord_channel_id {
+        return Ok(Some(format!(
+            "**{}** already announces in {}.",
+            channel.ch_description,
+            destination.id.mention()
+        )));
+    }
+
+    if let Some(response) = check_destination(ctx, destination, delivery, crosspost).await? {
+        return Ok(Some(response));
+    }
+
+    let taken = channels::Entity::find()
+        .filter(channels::Column::ChDiscordChannelId.eq(destination.id.0 as i64))
+        .filter(channels::Column::ChName.eq(channel.ch_name.clone()))
+        .one(db)
+        .await?
+        .is_some();
+
+    if taken {
+        return Ok(Some(format!(
+            "{} already follows this channel.",
+            destination.id.mention()
+        )));
+    }
+
+    let limit = guild_settings(ctx)
+        .await?
+        .and_then(|guild| guild.gu_channel_link_limit)
+        .map_or(CHANNEL_LINK_LIMIT, |limit| limit as u64);
+
+    let cnt = channels::Entity::find()
+        .filter(channels::Column::ChDiscordChannelId.eq(destination.id.0 as i64))
+        .count(db)
+        .await?;
+
+    if cnt >= limit {
+        return Ok(Some(format!(
+            "Too many linked channels in {} (limit: {limit}).",
+            destination.id.mention()
+        )));
+    }
+
+    // Announcements sent before the move stay where they are, remember where that is
+    posts::Entity::update_many()
+        .col_expr(
+            posts::Column::PoDiscordChannelId,
+            Expr::value(channel.ch_discord_channel_id),
+        )
+        .filter(posts::Column::PoChId.eq(channel.ch_id))
+        .filter(posts::Column::PoMessageId.is_not_null())
+        .filter(posts::Column::PoDiscordChannelId.is_null())
+        .exec(db)
+        .await?;
+
+    channels::ActiveModel {
+        ch_id: Set(channel.ch_id),
+        ch_discord_channel_id: Set(destination.id.0 as i64),
+        ch_crosspost: Set(crosspost),
+        ..Default::default()
+    }
+    .update(db)
+    .await?;
+
+    Ok(None)
+}
@@ -1,7 +1,7 @@
 use anyhow::Context;
 use migration::{Migrator, MigratorTrait};
 use poise::serenity_prelude::{self as sp, Activity};
-use post_checker::{reddit_posts, youtube_uploads};
+use post_checker::Registry;
 use sea_orm::{ConnectOptions, DatabaseConnection};
 use serenity::model::application::command::Command;
 use std::env;
@@ -12,16 +12,14 @@ use tracing::error;
 use tracing::log::LevelFilter;
 use tracing_subscriber::EnvFilter;
 
-use post_checker::Checker;
-
 mod commands;
 mod post_checker;
 
 struct Data {
     set_up_commands: AtomicBool,
     loop_running: AtomicBool,
-    debug_mode: bool,
     database: DatabaseConnection,
+    registry: Arc<Registry>,
     version: String,
 }
 
@@ -48,7 +46,7 @@ async fn register_commands<E>(
     Ok(())
 }
 
-async fn start_event_loop<'a, E>(
+async fn start_event_loop<E>(
     ctx: Arc<sp::Http>,
     framework: &poise::FrameworkContext<'_, Data, E>,
 ) -> Result<(), serenity::Error> {
@@ -60,42 +58,11 @@ async fn start_event_loop<'a, E>(
         return Ok(());
     }
 
-    event_loop_main(
-        ctx,
-        framework.user_data.database.clone(),
-        framework.user_data.debug_mode,
-    )
-    .await;
+    framework.user_data.registry.spawn(ctx);
 
     Ok(())
 }
 
-async fn event_loop_main<'a>(ctx: Arc<sp::Http>, database: DatabaseConnection, debug_mode: bool) {
-    let yt_checker = youtube_uploads::UploadChecker::new(debug_mode, database.clone()).await;
-    let yt_ctx = ctx.clone();
-    tokio::spawn(async move {
-        loop {
-            if let Err(err) = yt_checker.check(&yt_ctx).await {
-                error!("Failed to check for YouTube posts: {:?}", err);
-            }
-
-            tokio::time::sleep(Duration::from_secs(300)).await;
-        }
-    });
-
-    let reddit_checker = reddit_posts::PostChecker::new(debug_mode, database).await;
-    let reddit_ctx = ctx.clone();
-    tokio::spawn(async move {
-        loop {
-            if let Err(err) = reddit_checker.check(&reddit_ctx).await {
-                error!("Failed to check for Reddit posts: {:?}", err);
-            }
-
-            tokio::time::sleep(Duration::from_secs(300)).await;
-        }
-    });
-}
-
 fn handle_event<'a, E: From<serenity::Error>>(
     ctx: &'a sp::Context,
     event: &'a poise::Event<'a>,
@@ -112,6 +79,11 @@ fn handle_event<'a, E: From<serenity::Error>>(
             poise::Event::CacheReady { .. } => {
                 start_event_loop(ctx.http.clone(), &framework).await?;
             }
+            poise::Event::GuildCreate { guild, .. } => {
+                if let Err(err) = commands::register_guild(&data.database, guild).await {
+                    error!("Failed to register guild {}: {:?}", guild.id, err);
+                }
+            }
             _ => {}
         }
 
@@ -148,6 +120,13 @@ async fn main() -> anyhow::Result<()> {
     let database = sea_orm::Database::connect(opt).await?;
     Migrator::up(&database, None).await?;
 
+    let registry = Registry::new(debug_mode, database.clone()).await;
+    registry
+        .seed()
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+        .context("Failed to seed platforms")?;
+
     let token = env::var("DISCORD_TOKEN").expect("token");
     let intents = sp::GatewayIntents::non_privileged() | sp::GatewayIntents::MESSAGE_CONTENT;
     let framework = poise::Framework::builder()
@@ -157,6 +136,17 @@ async fn main() -> anyhow::Result<()> {
                 commands::add_channel(),
                 commands::list_channels(),
                 commands::remove_channel(),
+                commands::pause_channel(),
+                commands::resume_channel(),
+                commands::edit_channel(),
+                commands::move_channel(),
+                commands::set_template(),
+                commands::preview_template(),
+                commands::filter(),
+                commands::video_type(),
+                commands::status(),
+                commands::guild(),
+                commands::guild_limits(),
             ],
             prefix_options: poise::PrefixFrameworkOptions {
                 prefix: Some(">".to_string()),
@@ -175,8 +165,8 @@ async fn main() -> anyhow::Result<()> {
                 Ok(Data {
                     set_up_commands: false.into(),
                     loop_running: false.into(),
-                    debug_mode,
                     database,
+                    registry,
                     version: format!(
                         "{} v.{}, powered by crabs!",
                         env!("CARGO_PKG_NAME"),
@@ -0,0 +1,433 @@
+use chrono::{DateTime, Utc};
+use entity::channels;
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use std::env;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{Checker, Platform, Post, PostKind, Source};
+
+pub const PLATFORM: Platform = Platform {
+    name: "Bluesky",
+    description: "Bluesky",
+    interval: Duration::from_secs(300),
+    create: || Box::pin(async { Some(AuthorFeedChecker::new().await as Arc<dyn Checker>) }),
+};
+
+/// Public AppView serving unauthenticated XRPC reads.
+const DEFAULT_SERVICE: &str = "https://public.api.bsky.app";
+
+#[derive(Debug, Deserialize)]
+struct ResolveHandleResponse {
+    did: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthorFeedResponse {
+    feed: Vec<FeedViewPost>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedViewPost {
+    post: PostView,
+    reason: Option<Reason>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Reason {
+    #[serde(rename = "$type")]
+    kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PostView {
+    uri: String,
+    author: ProfileView,
+    record: PostRecord,
+    embed: Option<EmbedView>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileView {
+    handle: String,
+    display_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PostRecord {
+    #[serde(default)]
+    text: String,
+    reply: Option<serde_json::Value>,
+    created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbedView {
+    external: Option<ExternalView>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExternalView {
+    uri: String,
+}
+
+pub struct AuthorFeedChecker {
+    client: Client,
+    service: Url,
+}
+
+impl AuthorFeedChecker {
+    pub async fn new() -> Arc<AuthorFeedChecker> {
+        let service = env::var("BLUESKY_XRPC_URL").unwrap_or_else(|_| DEFAULT_SERVICE.to_owned());
+
+        Arc::new(Self::with_service(Url::parse(&service).unwrap()))
+    }
+
+    /// Creates a checker talking to the XRPC service at `service`.
+    pub fn with_service(service: Url) -> AuthorFeedChecker {
+        let client = reqwest::Client::builder()
+            .user_agent(concat!(
+                env!("CARGO_PKG_NAME"),
+                "/",
+                env!("CARGO_PKG_VERSION")
+            ))
+            .build()
+            .unwrap();
+
+        Self { client, service }
+    }
+
+    async fn xrpc<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T, Box<dyn Error + Send + Sync>> {
+        let url = self.service.join(&format!("xrpc/{method}"))?;
+
+        let response = self
+            .client
+            .get(url)
+            .query(query)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<T>()
+            .await?;
+
+        Ok(response)
+    }
+}
+
+#[async_trait::async_trait]
+impl Checker for AuthorFeedChecker {
+    async fn validate_id(&self, id: &str) -> Result<Source, String> {
+        let handle = id.trim().trim_end_matches('/');
+        let handle = handle
+            .strip_prefix("https://bsky.app/profile/")
+            .unwrap_or(handle)
+            .trim_start_matches('@');
+
+        if handle.starts_with("did:") {
+            return Ok(Source {
+                id: handle.to_owned(),
+                name: None,
+            });
+        }
+
+        if !handle.contains('.') || handle.contains('/') {
+            return Err(format!("**{id}** is not a valid Bluesky handle."));
+        }
+
+        match self
+            .xrpc::<ResolveHandleResponse>(
+                "com.atproto.identity.resolveHandle",
+                &[("handle", handle)],
+            )
+            .await
+        {
+            Ok(response) => Ok(Source {
+                id: response.did,
+                name: Some(format!("@{handle}")),
+            }),
+            Err(err) => Err(format!(
+                "Could not resolve Bluesky handle **{handle}**: {err}"
+            )),
+        }
+    }
+
+    async fn fetch(&self, id: &str) -> Result<Vec<Post>, Box<dyn Error + Send + Sync>> {
+        let response = self
+            .xrpc::<AuthorFeedResponse>("app.bsky.feed.getAuthorFeed", &[("actor", id)])
+            .await?;
+
+        Ok(response
+            .feed
+            .into_iter()
+            .map(|item| {
+                let post = item.post;
+
+                let kind = if item
+                    .reason
+                    .is_some_and(|r| r.kind == "app.bsky.feed.defs#reasonRepost")
+                {
+                    PostKind::Repost
+                } else if post.record.reply.is_some() {
+                    PostKind::Reply
+                } else {
+                    PostKind::Original
+                };
+
+                let rkey = post.uri.rsplit('/').next().unwrap_or_default();
+                let url = format!(
+                    "https://bsky.app/profile/{}/post/{rkey}",
+                    post.author.handle
+                );
+
+                // A repost shares the original post's URI, keep it apart from the original
+                let id = if kind == PostKind::Repost {
+                    format!("{}#repost:{id}", post.uri)
+                } else {
+                    post.uri
+                };
+
+                Post {
+                    id,
+                    url,
+                    text: Some(post.record.text).filter(|text| !text.is_empty()),
+                    link: post.embed.and_then(|e| e.external).map(|e| e.uri),
+                    // The feed's owner is the one reposting, not the original author
+                    author: Some(post.author.display_name.unwrap_or(post.author.handle))
+                        .filter(|name| !name.is_empty() && kind != PostKind::Repost),
+                    kind,
+                    published: post.record.created_at,
+                    ..Default::default()
+                }
+            })
+            .collect())
+    }
+
+    fn announcement(&self, channel: &channels::Model, post: &Post, mention: &str) -> String {
+        let author = post.author.as_ref().unwrap_or(&channel.ch_description);
+        let action = match post.kind {
+            PostKind::Original => "posted",
+            PostKind::Reply => "replied",
+            PostKind::Repost => "reposted",
+        };
+        let text = post.text.as_ref().map_or_else(String::new, |text| {
+            let quoted = text.lines().collect::<Vec<_>>().join("\n> ");
+            format!("\n> {quoted}")
+        });
+        let link = post
+            .link
+            .as_ref()
+            .map_or_else(String::new, |link| format!("\n{link}"));
+        let url = &post.url;
+
+        format!("Hey {mention}, **{author}** has {action} on Bluesky!{text}\n{url}{link}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::post_checker::registry::wants;
+    use crate::post_checker::RemovalAction;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const DID: &str = "did:plc:ewvi7nxzyoun6zhxrhs64oiz";
+
+    const AUTHOR_FEED: &str = r#"{
+        "feed": [
+            {
+                "post": {
+                    "uri": "at://did:plc:ewvi7nxzyoun6zhxrhs64oiz/app.bsky.feed.post/3kreply",
+                    "author": { "handle": "alice.bsky.social", "displayName": "Alice" },
+                    "record": {
+                        "text": "Agreed!",
+                        "reply": { "root": {}, "parent": {} },
+                        "createdAt": "2026-10-17T12:02:00Z"
+                    }
+                }
+            },
+            {
+                "post": {
+                    "uri": "at://did:plc:someoneelse/app.bsky.feed.post/3krepost",
+                    "author": { "handle": "bob.bsky.social", "displayName": "Bob" },
+                    "record": { "text": "Worth a read", "createdAt": "2026-10-17T12:01:00Z" }
+                },
+                "reason": { "$type": "app.bsky.feed.defs#reasonRepost" }
+            },
+            {
+                "post": {
+                    "uri": "at://did:plc:ewvi7nxzyoun6zhxrhs64oiz/app.bsky.feed.post/3kpost",
+                    "author": { "handle": "alice.bsky.social", "displayName": "Alice" },
+                    "record": { "text": "Hello, world", "createdAt": "2026-10-17T12:00:00Z" },
+                    "embed": { "external": { "uri": "https://example.com/article" } }
+                }
+            }
+        ]
+    }"#;
+
+    /// Serves canned XRPC responses on a local port, returns the service URL.
+    async fn xrpc_server() -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let service = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    let read = socket.read(&mut buf).await.unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..read]);
+                }
+
+                let request = String::from_utf8_lossy(&request);
+                let target = request.split(' ').nth(1).unwrap_or_default();
+
+                let (status, body) =
+                    if target.starts_with("/xrpc/com.atproto.identity.resolveHandle") {
+                        if target.contains("handle=alice.bsky.social") {
+                            ("200 OK", format!(r#"{{"did":"{DID}"}}"#))
+                        } else {
+                            (
+                            "400 Bad Request",
+                            r#"{"error":"InvalidRequest","message":"Unable to resolve handle"}"#
+                                .to_owned(),
+                        )
+                        }
+                    } else if target.starts_with("/xrpc/app.bsky.feed.getAuthorFeed") {
+                        ("200 OK", AUTHOR_FEED.to_owned())
+                    } else {
+                        ("404 Not Found", String::new())
+                    };
+
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        service
+    }
+
+    fn channel(show_replies: bool, show_reposts: bool) -> channels::Model {
+        channels::Model {
+            ch_id: 1,
+            ch_name: DID.to_owned(),
+            ch_description: "@alice.bsky.social".to_owned(),
+            ch_discord_channel_id: 1,
+            ch_pl_id: 1,
+            ch_role_mention_id: None,
+            ch_mention_flag: false,
+            ch_show_replies: show_replies,
+            ch_show_reposts: show_reposts,
+            ch_primed: true,
+            ch_backfill: 0,
+            ch_embed: false,
+            ch_template: None,
+            ch_on_removal: RemovalAction::Ignore.as_str().to_owned(),
+            ch_interval: None,
+            ch_gu_id: None,
+            ch_enabled: true,
+            ch_crosspost: false,
+            ch_delivery: "bot".to_owned(),
+        }
+    }
+
+    #[tokio::test]
+    async fn resolves_handles_to_dids() {
+        let checker = AuthorFeedChecker::with_service(xrpc_server().await);
+
+        for id in [
+            "alice.bsky.social",
+            "@alice.bsky.social",
+            "https://bsky.app/profile/alice.bsky.social/",
+        ] {
+            let source = checker.validate_id(id).await.unwrap();
+
+            assert_eq!(source.id, DID);
+            assert_eq!(source.name.as_deref(), Some("@alice.bsky.social"));
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_unresolvable_handles() {
+        let checker = AuthorFeedChecker::with_service(xrpc_server().await);
+
+        assert!(checker.validate_id("nobody.bsky.social").await.is_err());
+        assert!(checker.validate_id("alice").await.is_err());
+
+        // DIDs are taken as they are, without a lookup
+        let source = checker.validate_id(DID).await.unwrap();
+        assert_eq!(source.id, DID);
+        assert_eq!(source.name, None);
+    }
+
+    #[tokio::test]
+    async fn tells_replies_and_reposts_apart() {
+        let checker = AuthorFeedChecker::with_service(xrpc_server().await);
+        let posts = checker.fetch(DID).await.unwrap();
+
+        let kinds = posts.iter().map(|post| post.kind).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [PostKind::Reply, PostKind::Repost, PostKind::Original]
+        );
+
+        let repost = &posts[1];
+        assert_eq!(
+            repost.id,
+            format!("at://did:plc:someoneelse/app.bsky.feed.post/3krepost#repost:{DID}")
+        );
+        assert_eq!(repost.author, None);
+        assert_eq!(
+            repost.url,
+            "https://bsky.app/profile/bob.bsky.social/post/3krepost"
+        );
+
+        let original = &posts[2];
+        assert_eq!(original.author.as_deref(), Some("Alice"));
+        assert_eq!(
+            original.link.as_deref(),
+            Some("https://example.com/article")
+        );
+    }
+
+    #[tokio::test]
+    async fn filters_replies_and_reposts() {
+        let checker = AuthorFeedChecker::with_service(xrpc_server().await);
+        let posts = checker.fetch(DID).await.unwrap();
+
+        let wanted = |channel: &channels::Model| {
+            posts
+                .iter()
+                .filter(|post| wants(channel, post))
+                .map(|post| post.kind)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(wanted(&channel(false, false)), [PostKind::Original]);
+        assert_eq!(
+            wanted(&channel(true, false)),
+            [PostKind::Reply, PostKind::Original]
+        );
+        assert_eq!(
+            wanted(&channel(false, true)),
+            [PostKind::Repost, PostKind::Original]
+        );
+        assert_eq!(wanted(&channel(true, true)).len(), 3);
+    }
+}
@@ -0,0 +1,260 @@
+use poise::serenity_prelude::{
+    ChannelId, CreateMessage, EditMessage, Http, Message, MessageId, Webhook,
+};
+use reqwest::StatusCode;
+use serde_json::Value;
+use serenity::http::HttpError;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Mutex;
+
+use super::truncate;
+
+/// Name of the webhooks the bot creates for webhook delivery.
+const WEBHOOK_NAME: &str = "comae";
+
+/// Maximum length of a webhook message's username.
+const USERNAME_LEN: usize = 80;
+
+/// Discord's error code for a webhook that no longer exists.
+const UNKNOWN_WEBHOOK: isize = 10015;
+
+/// Who an announcement appears to come from, only webhooks can show it.
+pub struct Sender {
+    pub name: String,
+    pub avatar: Option<String>,
+}
+
+/// How announcements reach a link's Discord channel.
+#[async_trait::async_trait]
+pub trait Delivery: Send + Sync {
+    /// Sends a new announcement, returns the channel and ID of the message.
+    async fn send(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        sender: &Sender,
+        message: CreateMessage<'static>,
+    ) -> Result<(ChannelId, MessageId), Box<dyn Error + Send + Sync>>;
+
+    /// Replaces the content of an announcement sent through this delivery.
+    async fn edit(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        message: EditMessage<'static>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+
+    /// Deletes an announcement sent through this delivery.
+    async fn delete(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+/// Sends announcements as messages of the bot itself.
+pub struct BotDelivery;
+
+#[async_trait::async_trait]
+impl Delivery for BotDelivery {
+    async fn send(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        _sender: &Sender,
+        message: CreateMessage<'static>,
+    ) -> Result<(ChannelId, MessageId), Box<dyn Error + Send + Sync>> {
+        let message = channel_id
+            .send_message(http, |msg| {
+                *msg = message;
+                msg
+            })
+            .await?;
+
+        Ok((message.channel_id, message.id))
+    }
+
+    async fn edit(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        message: EditMessage<'static>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        channel_id
+            .edit_message(http, message_id, |msg| {
+                *msg = message;
+                msg
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(channel_id.delete_message(http, message_id).await?)
+    }
+}
+
+/// Sends announcements through a webhook of the channel, named and pictured after the source.
+#[derive(Default)]
+pub struct WebhookDelivery {
+    /// The bot's webhook of each channel, looked up or created on first use.
+    webhooks: Mutex<HashMap<ChannelId, Webhook>>,
+}
+
+impl WebhookDelivery {
+    async fn webhook(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+    ) -> Result<Webhook, Box<dyn Error + Send + Sync>> {
+        if let Some(webhook) = self.webhooks.lock().unwrap().get(&channel_id) {
+            return Ok(webhook.clone());
+        }
+
+        // Only webhooks the bot may use come with their token
+        let existing = channel_id
+            .webhooks(http)
+            .await?
+            .into_iter()
+            .find(|webhook| {
+                webhook.token.is_some() && webhook.name.as_deref() == Some(WEBHOOK_NAME)
+            });
+
+        let webhook = match existing {
+            Some(webhook) => webhook,
+            None => channel_id.create_webhook(http, WEBHOOK_NAME).await?,
+        };
+
+        self.webhooks
+            .lock()
+            .unwrap()
+            .insert(channel_id, webhook.clone());
+
+        Ok(webhook)
+    }
+
+    /// Forgets the webhook of `channel_id` if `err` says it was deleted, returns whether it did.
+    ///
+    /// The next announcement looks the webhook up again or creates a new one.
+    fn evict(&self, channel_id: ChannelId, err: &serenity::Error) -> bool {
+        let serenity::Error::Http(err) = err else {
+            return false;
+        };
+
+        let gone = match err.as_ref() {
+            HttpError::UnsuccessfulRequest(response) => {
+                response.error.code == UNKNOWN_WEBHOOK
+                    || response.status_code == StatusCode::NOT_FOUND
+            }
+            _ => false,
+        };
+
+        if gone {
+            self.webhooks.lock().unwrap().remove(&channel_id);
+        }
+
+        gone
+    }
+}
+
+async fn execute(
+    http: &Http,
+    webhook: &Webhook,
+    map: HashMap<&'static str, Value>,
+) -> Result<Option<Message>, serenity::Error> {
+    webhook
+        .execute(http, true, |msg| {
+            msg.0 = map;
+            msg
+        })
+        .await
+}
+
+#[async_trait::async_trait]
+impl Delivery for WebhookDelivery {
+    async fn send(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        sender: &Sender,
+        message: CreateMessage<'static>,
+    ) -> Result<(ChannelId, MessageId), Box<dyn Error + Send + Sync>> {
+        let webhook = self.webhook(http, channel_id).await?;
+
+        let mut map = message.0;
+        map.insert(
+            "username",
+            Value::from(truncate(&sender.name, USERNAME_LEN)),
+        );
+
+        if let Some(avatar) = &sender.avatar {
+            map.insert("avatar_url", Value::from(avatar.as_str()));
+        }
+
+        // Someone deleted the webhook since it was cached, send through a new one
+        let message = match execute(http, &webhook, map.clone()).await {
+            Err(err) if self.evict(channel_id, &err) => {
+                let webhook = self.webhook(http, channel_id).await?;
+                execute(http, &webhook, map).await?
+            }
+            res => res?,
+        }
+        .ok_or("webhook returned no message")?;
+
+        Ok((message.channel_id, message.id))
+    }
+
+    async fn edit(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        message: EditMessage<'static>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        // Messages of a deleted webhook can't be edited through a new one
+        let res = self
+            .webhook(http, channel_id)
+            .await?
+            .edit_message(http, message_id, |msg| {
+                msg.0 = message.0;
+                msg
+            })
+            .await;
+
+        if let Err(err) = &res {
+            self.evict(channel_id, err);
+        }
+
+        res?;
+        Ok(())
+    }
+
+    async fn delete(
+        &self,
+        http: &Http,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let res = self
+            .webhook(http, channel_id)
+            .await?
+            .delete_message(http, message_id)
+            .await;
+
+        if let Err(err) = &res {
+            self.evict(channel_id, err);
+        }
+
+        Ok(res?)
+    }
+}
@@ -0,0 +1,124 @@
+use entity::channels;
+use reqwest::{Client, Url};
+use std::borrow::Cow;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::http::HttpCache;
+use super::{fetch_rss, Checker, Platform, Post, Source};
+
+pub const PLATFORM: Platform = Platform {
+    name: "Feed",
+    description: "RSS/Atom feed",
+    interval: Duration::from_secs(900),
+    create: || Box::pin(async { Some(EntryChecker::new().await as Arc<dyn Checker>) }),
+};
+
+/// Maximum length of a feed URL, matches the width of `channels.ch_name`.
+const MAX_URL_LEN: usize = 512;
+
+pub struct EntryChecker {
+    client: Client,
+    cache: HttpCache,
+}
+
+impl EntryChecker {
+    pub async fn new() -> Arc<EntryChecker> {
+        let client = reqwest::Client::builder()
+            .user_agent(concat!(
+                env!("CARGO_PKG_NAME"),
+                "/",
+                env!("CARGO_PKG_VERSION")
+            ))
+            .build()
+            .unwrap();
+
+        Arc::new(Self {
+            client,
+            cache: HttpCache::default(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Checker for EntryChecker {
+    async fn validate_id(&self, id: &str) -> Result<Source, String> {
+        let id = id.trim();
+
+        let url = match Url::parse(id) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => url,
+            _ => return Err(format!("**{id}** is not a valid feed URL.")),
+        };
+
+        if url.as_str().len() > MAX_URL_LEN {
+            return Err(format!(
+                "Feed URLs can be at most {MAX_URL_LEN} characters long."
+            ));
+        }
+
+        match fetch_rss(&self.client, &self.cache, Cow::Borrowed(url.as_str())).await {
+            Ok(feed) => Ok(Source {
+                id: url.into(),
+                name: feed.title.map(|title| title.content),
+            }),
+            Err(err) => Err(format!("Could not read a feed from **{url}**: {err}")),
+        }
+    }
+
+    async fn fetch(&self, id: &str) -> Result<Vec<Post>, Box<dyn Error + Send + Sync>> {
+        let mut feed = fetch_rss(&self.client, &self.cache, Cow::Borrowed(id)).await?;
+
+        let source = feed.title.map(|title| title.content);
+
+        // Feeds aren't required to list entries newest first
+        feed.entries
+            .sort_by_key(|entry| std::cmp::Reverse(entry.published.or(entry.updated)));
+
+        Ok(feed
+            .entries
+            .into_iter()
+            .filter_map(|entry| {
+                // Entries without a link are skipped unless their ID is a web address
+                let url = entry
+                    .links
+                    .into_iter()
+                    .next()
+                    .map(|link| link.href)
+                    .or_else(|| {
+                        (entry.id.starts_with("https://") || entry.id.starts_with("http://"))
+                            .then(|| entry.id.clone())
+                    })?;
+
+                Some(Post {
+                    id: entry.id,
+                    url,
+                    title: entry.title.map(|title| title.content),
+                    text: entry.summary.map(|summary| summary.content),
+                    author: entry.authors.into_iter().next().map(|author| author.name),
+                    source: source.clone(),
+                    thumbnail: entry
+                        .media
+                        .into_iter()
+                        .flat_map(|media| media.thumbnails)
+                        .next()
+                        .map(|thumbnail| thumbnail.image.uri),
+                    published: entry.published.or(entry.updated),
+                    ..Default::default()
+                })
+            })
+            .collect())
+    }
+
+    fn announcement(&self, channel: &channels::Model, post: &Post, mention: &str) -> String {
+        let feed = &channel.ch_description;
+        let title = post.title.as_deref().unwrap_or("a new entry");
+        let author = post
+            .author
+            .as_ref()
+            .map_or_else(String::new, |author| format!(" by **{author}**"));
+        let url = &post.url;
+
+        format!("Hey {mention}, **{feed}** has published **{title}**{author}!\n{url}")
+    }
+}
@@ -0,0 +1,174 @@
+use entity::filters;
+use regex::{Regex, RegexBuilder};
+
+use super::Post;
+
+/// Maximum length of a filter value, matches the width of `filters.fi_value`.
+pub const VALUE_LEN: usize = 256;
+
+/// Compiled size limit for title patterns, keeps a single rule from hogging memory.
+const REGEX_SIZE_LIMIT: usize = 1 << 16;
+
+/// The kind of a filter rule, stored in `filters.fi_kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum FilterKind {
+    #[name = "Title matches"]
+    TitleInclude,
+    #[name = "Title doesn't match"]
+    TitleExclude,
+    #[name = "Flair is"]
+    Flair,
+    #[name = "Author is"]
+    AuthorAllow,
+    #[name = "Author isn't"]
+    AuthorBlock,
+    #[name = "Score is at least"]
+    MinScore,
+}
+
+impl FilterKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilterKind::TitleInclude => "title_include",
+            FilterKind::TitleExclude => "title_exclude",
+            FilterKind::Flair => "flair",
+            FilterKind::AuthorAllow => "author_allow",
+            FilterKind::AuthorBlock => "author_block",
+            FilterKind::MinScore => "min_score",
+        }
+    }
+
+    /// Checks and normalizes a user-supplied value, the error is shown to the user as-is.
+    pub fn validate(&self, value: &str) -> Result<String, String> {
+        let value = value.trim();
+
+        if value.is_empty() {
+            return Err("Filter values can't be empty.".to_owned());
+        }
+
+        if value.chars().count() > VALUE_LEN {
+            return Err(format!(
+                "Filter values can be at most {VALUE_LEN} characters long."
+            ));
+        }
+
+        match self {
+            FilterKind::TitleInclude | FilterKind::TitleExclude => compile(value)
+                .map(|_| value.to_owned())
+                .map_err(|err| format!("**{value}** is not a valid pattern: {err}")),
+            FilterKind::Flair => Ok(value.to_owned()),
+            FilterKind::AuthorAllow | FilterKind::AuthorBlock => Ok(normalize_author(value)),
+            FilterKind::MinScore => value
+                .parse::<i64>()
+                .map(|score| score.to_string())
+                .map_err(|_| format!("**{value}** is not a whole number.")),
+        }
+    }
+
+    /// Parses the value stored in `filters.fi_kind`.
+    pub fn from_column(s: &str) -> Option<FilterKind> {
+        [
+            FilterKind::TitleInclude,
+            FilterKind::TitleExclude,
+            FilterKind::Flair,
+            FilterKind::AuthorAllow,
+            FilterKind::AuthorBlock,
+            FilterKind::MinScore,
+        ]
+        .into_iter()
+        .find(|kind| kind.as_str() == s)
+    }
+}
+
+fn compile(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+}
+
+/// Compares authors without the `/u/` prefix Reddit posts carry.
+fn normalize_author(author: &str) -> String {
+    let author = author.trim().trim_start_matches('/');
+
+    author
+        .strip_prefix("u/")
+        .unwrap_or(author)
+        .trim_start_matches('@')
+        .to_lowercase()
+}
+
+/// The filter rules of a single link.
+///
+/// Title patterns, flairs and allowed authors each let a post through, so a post has to
+/// match at least one of them when any are set. Excluded titles and blocked authors
+/// always drop a post, as does a score below the minimum.
+#[derive(Default)]
+pub struct Rules {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+    flairs: Vec<String>,
+    allowed_authors: Vec<String>,
+    blocked_authors: Vec<String>,
+    min_score: Option<i64>,
+}
+
+impl Rules {
+    pub fn new(filters: &[filters::Model]) -> Rules {
+        let mut rules = Rules::default();
+
+        for filter in filters {
+            // Rows are validated on insert, skip anything that no longer parses
+            let Some(kind) = FilterKind::from_column(&filter.fi_kind) else {
+                continue;
+            };
+            let value = &filter.fi_value;
+
+            match kind {
+                FilterKind::TitleInclude => rules.include.extend(compile(value).ok()),
+                FilterKind::TitleExclude => rules.exclude.extend(compile(value).ok()),
+                FilterKind::Flair => rules.flairs.push(value.to_lowercase()),
+                FilterKind::AuthorAllow => rules.allowed_authors.push(normalize_author(value)),
+                FilterKind::AuthorBlock => rules.blocked_authors.push(normalize_author(value)),
+                FilterKind::MinScore => {
+                    let score = value.parse().ok();
+                    rules.min_score = rules.min_score.max(score);
+                }
+            }
+        }
+
+        rules
+    }
+
+    pub fn allows(&self, post: &Post) -> bool {
+        let title = post.title.as_deref().or(post.text.as_deref()).unwrap_or("");
+        let author = post.author.as_deref().map(normalize_author);
+        let flair = post.category.as_deref().map(str::to_lowercase);
+
+        if self.exclude.iter().any(|re| re.is_match(title)) {
+            return false;
+        }
+
+        if author
+            .as_ref()
+            .is_some_and(|author| self.blocked_authors.contains(author))
+        {
+            return false;
+        }
+
+        // Posts without a score come from platforms that don't report one
+        if let (Some(min), Some(score)) = (self.min_score, post.score) {
+            if score < min {
+                return false;
+            }
+        }
+
+        if self.include.is_empty() && self.flairs.is_empty() && self.allowed_authors.is_empty() {
+            return true;
+        }
+
+        self.include.iter().any(|re| re.is_match(title))
+            || flair.is_some_and(|flair| self.flairs.contains(&flair))
+            || author.is_some_and(|author| self.allowed_authors.contains(&author))
+    }
+}
@@ -0,0 +1,100 @@
+use poise::serenity_prelude::{json, ChannelId, CreateMessage, Http, MessageId};
+use serde::Deserialize;
+use serde_json::Value;
+use serenity::http::request::RequestBuilder;
+use serenity::http::routing::RouteInfo;
+use std::error::Error;
+
+use super::{truncate, Post};
+
+/// `type` of a forum channel in the Discord API.
+const FORUM_CHANNEL: u8 = 15;
+
+/// Maximum length of a thread name.
+const THREAD_NAME_LEN: usize = 100;
+
+/// Maximum number of tags applied to a forum thread.
+const MAX_TAGS: usize = 5;
+
+/// The parts of a channel serenity doesn't model yet.
+#[derive(Deserialize)]
+struct RawChannel {
+    #[serde(rename = "type")]
+    kind: u8,
+    #[serde(default)]
+    available_tags: Vec<Tag>,
+}
+
+#[derive(Deserialize)]
+pub struct Tag {
+    id: String,
+    name: String,
+}
+
+/// The tags of `channel_id` if it's a forum channel, `None` for any other channel.
+pub async fn tags(
+    http: &Http,
+    channel_id: ChannelId,
+) -> Result<Option<Vec<Tag>>, Box<dyn Error + Send + Sync>> {
+    let request = RequestBuilder::new(RouteInfo::GetChannel {
+        channel_id: channel_id.0,
+    })
+    .build();
+
+    let channel = http.fire::<RawChannel>(request).await?;
+
+    Ok((channel.kind == FORUM_CHANNEL).then_some(channel.available_tags))
+}
+
+/// Opens a thread for `post` in a forum channel, starting with `message`.
+///
+/// Returns the thread and its starter message, which share their ID.
+pub async fn create_thread(
+    http: &Http,
+    channel_id: ChannelId,
+    tags: &[Tag],
+    post: &Post,
+    message: CreateMessage<'_>,
+) -> Result<(ChannelId, MessageId), Box<dyn Error + Send + Sync>> {
+    let name = post
+        .title
+        .as_deref()
+        .or(post.text.as_deref())
+        .or(post.source.as_deref())
+        .unwrap_or(&post.url);
+
+    let mut map = json::JsonMap::new();
+    map.insert(
+        "name".to_owned(),
+        Value::from(truncate(name.trim(), THREAD_NAME_LEN)),
+    );
+    map.insert("applied_tags".to_owned(), Value::from(applied(tags, post)));
+    map.insert(
+        "message".to_owned(),
+        Value::from(json::hashmap_to_json_map(message.0)),
+    );
+
+    // Creating a thread in a forum channel posts its starter message along with it
+    let thread = http.create_private_thread(channel_id.0, &map).await?;
+
+    Ok((thread.id, MessageId(thread.id.0)))
+}
+
+/// The forum tags named like the post's flair or video type.
+fn applied(tags: &[Tag], post: &Post) -> Vec<String> {
+    let labels = [
+        post.category.as_deref(),
+        post.video.map(|video| video.name()),
+    ];
+
+    tags.iter()
+        .filter(|tag| {
+            labels
+                .iter()
+                .flatten()
+                .any(|label| tag.name.trim().eq_ignore_ascii_case(label.trim()))
+        })
+        .map(|tag| tag.id.clone())
+        .take(MAX_TAGS)
+        .collect()
+}
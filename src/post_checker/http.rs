@@ -0,0 +1,183 @@
+use chrono::{DateTime, Utc};
+use reqwest::header::{
+    HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER,
+};
+use reqwest::{Client, Response, StatusCode, Url};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// Returned instead of making a request while a host asked us to back off.
+///
+/// The scheduler waits this long before checking the source again, without counting it
+/// as a failure.
+#[derive(Debug)]
+pub struct RetryAfter(pub Duration);
+
+impl fmt::Display for RetryAfter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rate limited, retrying in {}s", self.0.as_secs())
+    }
+}
+
+impl Error for RetryAfter {}
+
+/// Most responses kept for conditional requests, the cache starts over when it fills up.
+const RESPONSE_CACHE_LEN: usize = 1024;
+
+/// The validators and body of the last successful response for a URL.
+struct Cached {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+    body: Vec<u8>,
+}
+
+/// Conditional GET requests and per-host rate limit tracking.
+#[derive(Default)]
+pub struct HttpCache {
+    responses: RwLock<HashMap<String, Cached>>,
+    blocked: RwLock<HashMap<String, Instant>>,
+}
+
+impl HttpCache {
+    /// Fetches `url`, answering from the cache when the server reports it unchanged.
+    ///
+    /// A 304 yields the previous body, so nothing in it is new to the caller.
+    pub async fn get(
+        &self,
+        client: &Client,
+        url: &str,
+    ) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        let host = Url::parse(url)?.host_str().unwrap_or_default().to_owned();
+
+        if let Some(&until) = self.blocked.read().unwrap().get(&host) {
+            let now = Instant::now();
+
+            if until > now {
+                return Err(RetryAfter(until - now).into());
+            }
+        }
+
+        let mut response = self.send(client, url, &host, true).await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = self.responses.read().unwrap().get(url) {
+                return Ok(cached.body.clone());
+            }
+
+            // The cached body is gone, e.g. after the cache filled up, ask for the full response
+            response = self.send(client, url, &host, false).await?;
+        }
+
+        let response = response.error_for_status()?;
+
+        let etag = response.headers().get(ETAG).cloned();
+        let last_modified = response.headers().get(LAST_MODIFIED).cloned();
+        let body = response.bytes().await?.to_vec();
+
+        let mut responses = self.responses.write().unwrap();
+
+        if etag.is_some() || last_modified.is_some() {
+            if responses.len() >= RESPONSE_CACHE_LEN && !responses.contains_key(url) {
+                responses.clear();
+            }
+
+            responses.insert(
+                url.to_owned(),
+                Cached {
+                    etag,
+                    last_modified,
+                    body: body.clone(),
+                },
+            );
+        } else {
+            responses.remove(url);
+        }
+
+        Ok(body)
+    }
+
+    /// Requests `url`, conditionally if asked and a previous response is cached.
+    ///
+    /// Notes when the host asks us to back off, failing right away if it refused the request.
+    async fn send(
+        &self,
+        client: &Client,
+        url: &str,
+        host: &str,
+        conditional: bool,
+    ) -> Result<Response, Box<dyn Error + Send + Sync>> {
+        let mut request = client.get(url);
+
+        if let Some(cached) = self
+            .responses
+            .read()
+            .unwrap()
+            .get(url)
+            .filter(|_| conditional)
+        {
+            if let Some(etag) = &cached.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = request.send().await?;
+
+        if let Some(wait) = backoff(response.status(), response.headers()) {
+            let now = Instant::now();
+            let mut blocked = self.blocked.write().unwrap();
+
+            // Forget hosts that are no longer blocked
+            blocked.retain(|_, until| *until > now);
+            blocked.insert(host.to_owned(), now + wait);
+
+            if matches!(
+                response.status(),
+                StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+            ) {
+                return Err(RetryAfter(wait).into());
+            }
+        }
+
+        Ok(response)
+    }
+}
+
+/// How long a host wants us to wait, from `Retry-After` or Reddit's `x-ratelimit-*` headers.
+fn backoff(status: StatusCode, headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+    let retry_after = header(RETRY_AFTER.as_str()).and_then(|value| {
+        value
+            .parse::<u64>()
+            .map(Duration::from_secs)
+            .ok()
+            .or_else(|| {
+                let date = DateTime::parse_from_rfc2822(value).ok()?;
+                (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+            })
+    });
+
+    if retry_after.is_some() {
+        return retry_after;
+    }
+
+    let remaining = header("x-ratelimit-remaining").and_then(|value| value.parse::<f64>().ok());
+    let reset = header("x-ratelimit-reset")
+        .and_then(|value| value.parse::<u64>().ok())
+        .map(Duration::from_secs);
+
+    match (remaining, reset) {
+        (Some(remaining), Some(reset)) if remaining < 1.0 => Some(reset),
+        (_, reset) if status == StatusCode::TOO_MANY_REQUESTS => {
+            Some(reset.unwrap_or(Duration::from_secs(60)))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,312 @@
+use chrono::{DateTime, Utc};
+use entity::channels;
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use super::{Checker, Platform, Post, PostKind, Source};
+
+pub const PLATFORM: Platform = Platform {
+    name: "Mastodon",
+    description: "Mastodon",
+    interval: Duration::from_secs(300),
+    create: || Box::pin(async { Some(StatusChecker::new().await as Arc<dyn Checker>) }),
+};
+
+#[derive(Debug, Deserialize)]
+struct WebFinger {
+    subject: String,
+    #[serde(default)]
+    links: Vec<WebFingerLink>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebFingerLink {
+    rel: String,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    href: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Account {
+    id: String,
+    acct: String,
+    display_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Status {
+    uri: String,
+    created_at: DateTime<Utc>,
+    url: Option<String>,
+    /// HTML body of the status.
+    #[serde(default)]
+    content: String,
+    in_reply_to_id: Option<String>,
+    reblog: Option<Box<Status>>,
+    account: Account,
+}
+
+/// Where the statuses of an account can be read from.
+#[derive(Debug, Clone)]
+struct ResolvedAccount {
+    /// Host serving the Mastodon API, may differ from the domain in the handle.
+    host: String,
+    account_id: String,
+    display_name: String,
+}
+
+pub struct StatusChecker {
+    client: Client,
+    accounts: RwLock<HashMap<String, ResolvedAccount>>,
+}
+
+impl StatusChecker {
+    pub async fn new() -> Arc<StatusChecker> {
+        let client = reqwest::Client::builder()
+            .https_only(true)
+            .user_agent(concat!(
+                env!("CARGO_PKG_NAME"),
+                "/",
+                env!("CARGO_PKG_VERSION")
+            ))
+            .build()
+            .unwrap();
+
+        Arc::new(Self {
+            client,
+            accounts: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Resolves `user@domain` through WebFinger, returning the canonical handle.
+    async fn webfinger(
+        &self,
+        user: &str,
+        domain: &str,
+    ) -> Result<(String, ResolvedAccount), Box<dyn Error + Send + Sync>> {
+        let webfinger = self
+            .client
+            .get(format!("https://{domain}/.well-known/webfinger"))
+            .query(&[("resource", format!("acct:{user}@{domain}"))])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<WebFinger>()
+            .await?;
+
+        let acct = webfinger
+            .subject
+            .strip_prefix("acct:")
+            .ok_or("WebFinger subject is not an account")?
+            .to_owned();
+
+        let actor = webfinger
+            .links
+            .iter()
+            .find(|link| {
+                link.rel == "self" && link.kind.as_deref() == Some("application/activity+json")
+            })
+            .and_then(|link| link.href.as_deref())
+            .ok_or("WebFinger response has no ActivityPub actor")?;
+
+        let host = Url::parse(actor)?
+            .host_str()
+            .ok_or("ActivityPub actor has no host")?
+            .to_owned();
+
+        let account = self
+            .client
+            .get(format!("https://{host}/api/v1/accounts/lookup"))
+            .query(&[("acct", &acct)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Account>()
+            .await?;
+
+        Ok((
+            format!("@{acct}"),
+            ResolvedAccount {
+                host,
+                account_id: account.id,
+                display_name: account.display_name,
+            },
+        ))
+    }
+
+    async fn resolve(&self, handle: &str) -> Result<ResolvedAccount, Box<dyn Error + Send + Sync>> {
+        if let Some(account) = self.accounts.read().unwrap().get(handle) {
+            return Ok(account.clone());
+        }
+
+        let (user, domain) = split_handle(handle).ok_or("Malformed Mastodon handle")?;
+        let (_, account) = self.webfinger(user, domain).await?;
+
+        self.accounts
+            .write()
+            .unwrap()
+            .insert(handle.to_owned(), account.clone());
+
+        Ok(account)
+    }
+}
+
+/// Splits `@user@domain` or `https://domain/@user` into its user and domain parts.
+fn split_handle(handle: &str) -> Option<(&str, &str)> {
+    let handle = handle.trim();
+
+    if let Some(rest) = handle.strip_prefix("https://") {
+        let (domain, path) = rest.split_once('/')?;
+        let user = path.strip_prefix('@')?.trim_end_matches('/');
+        return Some((user, domain)).filter(|(user, _)| !user.contains('/'));
+    }
+
+    let (user, domain) = handle.trim_start_matches('@').split_once('@')?;
+
+    Some((user, domain)).filter(|(user, domain)| !user.is_empty() && !domain.is_empty())
+}
+
+#[async_trait::async_trait]
+impl Checker for StatusChecker {
+    async fn validate_id(&self, id: &str) -> Result<Source, String> {
+        let Some((user, domain)) = split_handle(id) else {
+            return Err(format!(
+                "**{id}** is not a valid Mastodon handle, expected `@user@instance`."
+            ));
+        };
+
+        match self.webfinger(user, domain).await {
+            Ok((handle, account)) => {
+                let name = Some(account.display_name.clone()).filter(|name| !name.is_empty());
+
+                self.accounts
+                    .write()
+                    .unwrap()
+                    .insert(handle.clone(), account);
+
+                Ok(Source { id: handle, name })
+            }
+            Err(err) => Err(format!("Could not find Mastodon account **{id}**: {err}")),
+        }
+    }
+
+    async fn fetch(&self, id: &str) -> Result<Vec<Post>, Box<dyn Error + Send + Sync>> {
+        let account = self.resolve(id).await?;
+
+        let statuses = self
+            .client
+            .get(format!(
+                "https://{}/api/v1/accounts/{}/statuses",
+                account.host, account.account_id
+            ))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<Status>>()
+            .await?;
+
+        Ok(statuses
+            .into_iter()
+            .map(|status| {
+                let kind = if status.reblog.is_some() {
+                    PostKind::Repost
+                } else if status.in_reply_to_id.is_some() {
+                    PostKind::Reply
+                } else {
+                    PostKind::Original
+                };
+
+                let shown = status.reblog.as_deref().unwrap_or(&status);
+                let url = shown.url.clone().unwrap_or_else(|| shown.uri.clone());
+                let text = plain_text(&shown.content);
+
+                let author = if status.account.display_name.is_empty() {
+                    status.account.acct
+                } else {
+                    status.account.display_name
+                };
+
+                Post {
+                    id: status.uri,
+                    url,
+                    text: Some(text).filter(|text| !text.is_empty()),
+                    author: Some(author),
+                    kind,
+                    published: Some(status.created_at),
+                    ..Default::default()
+                }
+            })
+            .collect())
+    }
+
+    fn announcement(&self, channel: &channels::Model, post: &Post, mention: &str) -> String {
+        let author = post.author.as_ref().unwrap_or(&channel.ch_description);
+        let action = match post.kind {
+            PostKind::Original => "posted",
+            PostKind::Reply => "replied",
+            PostKind::Repost => "boosted a post",
+        };
+        let url = &post.url;
+
+        format!("Hey {mention}, **{author}** has {action} on Mastodon!\n{url}")
+    }
+}
+
+/// The text of a status body, Mastodon sends it as HTML paragraphs.
+fn plain_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+
+        let tag = rest[start + 1..start + end].trim().to_ascii_lowercase();
+
+        // Keep the line structure of the status
+        if tag.starts_with("br") {
+            text.push('\n');
+        } else if tag == "/p" {
+            text.push_str("\n\n");
+        }
+
+        rest = &rest[start + end + 1..];
+    }
+
+    text.push_str(rest);
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+        .trim()
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_status_html() {
+        let html = "<p>Hello <a href=\"https://example.com\" rel=\"nofollow\"><span>example.com</span></a> &amp; friends</p><p>Second<br />line &lt;3</p>";
+
+        assert_eq!(
+            plain_text(html),
+            "Hello example.com & friends\n\nSecond\nline <3"
+        );
+        assert_eq!(plain_text(""), "");
+    }
+}
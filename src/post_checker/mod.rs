@@ -1,32 +1,330 @@
+pub mod bluesky_posts;
+pub mod delivery;
+pub mod feed_entries;
+pub mod filter;
+pub mod forum;
+pub mod http;
+pub mod mastodon_statuses;
+pub mod quota;
 pub mod reddit_posts;
+pub mod registry;
+pub mod scheduler;
+pub mod template;
+pub mod twitch_streams;
 pub mod youtube_uploads;
 
 use std::{borrow::Cow, error::Error};
 
+use chrono::{DateTime, Utc};
+use entity::channels;
 use feed_rs::model::Feed;
+use poise::serenity_prelude::CreateEmbed;
+use poise::BoxFuture;
 use reqwest::Client;
+use std::sync::Arc;
+use std::time::Duration;
+
+use http::HttpCache;
+pub use registry::Registry;
+
+/// Every platform the bot knows about.
+///
+/// This is the single place a new checker has to be registered in, slash command
+/// choices, the `platforms` table and the polling loops are all derived from it.
+pub static PLATFORMS: &[Platform] = &[
+    youtube_uploads::PLATFORM,
+    reddit_posts::PLATFORM,
+    feed_entries::PLATFORM,
+    twitch_streams::PLATFORM,
+    mastodon_statuses::PLATFORM,
+    bluesky_posts::PLATFORM,
+];
+
+/// Static description of a platform and how to construct its checker.
+pub struct Platform {
+    /// Unique name, stored in `platforms.pl_name` and shown in slash command choices.
+    pub name: &'static str,
+    pub description: &'static str,
+    /// How often links are checked unless they set their own interval.
+    pub interval: Duration,
+    /// Builds the checker, `None` when the platform can't run, e.g. for lack of credentials.
+    pub create: fn() -> BoxFuture<'static, Option<Arc<dyn Checker>>>,
+}
+
+impl Platform {
+    pub fn find(name: &str) -> Option<&'static Platform> {
+        PLATFORMS
+            .iter()
+            .find(|platform| platform.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// A single entry fetched from a source.
+#[derive(Debug, Clone, Default)]
+pub struct Post {
+    /// Platform-specific ID used for deduplication.
+    pub id: String,
+    pub url: String,
+    pub title: Option<String>,
+    /// Body text, for platforms where posts have no title.
+    pub text: Option<String>,
+    /// A link embedded in the post, if any.
+    pub link: Option<String>,
+    pub author: Option<String>,
+    pub source: Option<String>,
+    /// Flair, game or similar classification, if the platform has one.
+    pub category: Option<String>,
+    pub kind: PostKind,
+    /// Type of a video, for platforms that tell them apart.
+    pub video: Option<VideoKind>,
+    /// Broadcast state of a premiere or livestream, the announcement is edited when it changes.
+    pub live: Option<LiveState>,
+    /// When an upcoming broadcast is scheduled to start.
+    pub scheduled: Option<DateTime<Utc>>,
+    /// Preview image or video thumbnail.
+    pub thumbnail: Option<String>,
+    pub published: Option<DateTime<Utc>>,
+    /// Length of a video.
+    pub duration: Option<Duration>,
+    pub score: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PostKind {
+    #[default]
+    Original,
+    /// A reply to another post, only announced when `ch_show_replies` is set.
+    Reply,
+    /// A boost or repost, only announced when `ch_show_reposts` is set.
+    Repost,
+}
+
+/// The type of a video, each can be toggled per link with `/video_type`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum VideoKind {
+    #[default]
+    #[name = "Video"]
+    Video,
+    #[name = "Short"]
+    Short,
+    /// Premiere, announced as soon as it is scheduled.
+    #[name = "Premiere"]
+    Premiere,
+    /// Livestream, announced as soon as it is scheduled or goes live.
+    #[name = "Livestream"]
+    Stream,
+}
+
+impl VideoKind {
+    pub const ALL: [VideoKind; 4] = [
+        VideoKind::Video,
+        VideoKind::Short,
+        VideoKind::Premiere,
+        VideoKind::Stream,
+    ];
+
+    /// The value stored in `video_kinds.vk_kind`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VideoKind::Video => "video",
+            VideoKind::Short => "short",
+            VideoKind::Premiere => "premiere",
+            VideoKind::Stream => "stream",
+        }
+    }
+}
+
+/// What happens to an announcement once its post is removed upstream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum RemovalAction {
+    #[default]
+    #[name = "Keep the announcement"]
+    Ignore,
+    #[name = "Mark the announcement as removed"]
+    Note,
+    #[name = "Delete the announcement"]
+    Delete,
+}
+
+impl RemovalAction {
+    /// The value stored in `channels.ch_on_removal`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RemovalAction::Ignore => "ignore",
+            RemovalAction::Note => "note",
+            RemovalAction::Delete => "delete",
+        }
+    }
+
+    pub fn from_column(s: &str) -> RemovalAction {
+        match s {
+            "note" => RemovalAction::Note,
+            "delete" => RemovalAction::Delete,
+            _ => RemovalAction::Ignore,
+        }
+    }
+}
+
+/// How a link's announcements are sent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum DeliveryMethod {
+    #[default]
+    #[name = "Bot message"]
+    Bot,
+    /// A webhook named and pictured after the source.
+    #[name = "Webhook"]
+    Webhook,
+}
+
+impl DeliveryMethod {
+    /// The value stored in `channels.ch_delivery` and `posts.po_delivery`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryMethod::Bot => "bot",
+            DeliveryMethod::Webhook => "webhook",
+        }
+    }
+
+    pub fn from_column(s: &str) -> DeliveryMethod {
+        match s {
+            "webhook" => DeliveryMethod::Webhook,
+            _ => DeliveryMethod::Bot,
+        }
+    }
+}
+
+/// Progress of a premiere or livestream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveState {
+    Upcoming,
+    Live,
+    Ended,
+}
+
+impl LiveState {
+    /// The value stored in `posts.po_state`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LiveState::Upcoming => "upcoming",
+            LiveState::Live => "live",
+            LiveState::Ended => "ended",
+        }
+    }
+}
+
+/// A validated source, as returned by [`Checker::validate_id`].
+#[derive(Debug, Clone)]
+pub struct Source {
+    /// Canonical ID, stored in `channels.ch_name`.
+    pub id: String,
+    /// Human readable name of the source, if the platform reports one.
+    pub name: Option<String>,
+}
 
 #[async_trait::async_trait]
-pub trait Checker {
-    fn name(&self) -> &str;
+pub trait Checker: Send + Sync {
+    /// Validates a user-supplied source ID and resolves it to its canonical form.
+    ///
+    /// The error is shown to the user as-is.
+    async fn validate_id(&self, id: &str) -> Result<Source, String>;
+
+    /// Fetches the most recent entries of the source identified by `id`, newest first.
+    async fn fetch(&self, id: &str) -> Result<Vec<Post>, Box<dyn Error + Send + Sync>>;
+
+    /// Fills in details of a new post that are too expensive to fetch for every entry.
+    ///
+    /// Only called right before a post is announced.
+    async fn enrich(&self, _post: &mut Post) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(())
+    }
 
-    async fn check(
-        &self,
-        ctx: impl AsRef<serenity::http::Http> + Send + Sync,
-    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+    /// Returns which of the given previously fetched post IDs were removed upstream.
+    ///
+    /// Platforms that can't tell report nothing as removed.
+    async fn removed(&self, _ids: &[String]) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+
+    /// The icon of the source identified by `id`, shown on webhook announcements.
+    async fn avatar(&self, _id: &str) -> Result<Option<String>, Box<dyn Error + Send + Sync>> {
+        Ok(None)
+    }
+
+    /// Adjusts how often sources are checked, e.g. to stay within an API quota.
+    fn pace(&self, interval: Duration) -> Duration {
+        interval
+    }
+
+    /// Describes the state of the checker for `/status`, if there is anything to report.
+    fn status(&self) -> Option<String> {
+        None
+    }
+
+    /// Formats the announcement for a new post.
+    fn announcement(&self, channel: &channels::Model, post: &Post, mention: &str) -> String;
+
+    /// Builds the embed for links using the embed announcement style.
+    fn embed(&self, channel: &channels::Model, post: &Post, embed: &mut CreateEmbed) {
+        base_embed(channel, post, embed);
+    }
+}
+
+/// Embed limits, see <https://discord.com/developers/docs/resources/channel#embed-object-embed-limits>.
+const EMBED_TITLE_LEN: usize = 256;
+const EMBED_DESCRIPTION_LEN: usize = 4096;
+
+fn truncate(text: &str, len: usize) -> String {
+    if text.chars().count() <= len {
+        return text.to_owned();
+    }
+
+    let mut truncated = text.chars().take(len - 1).collect::<String>();
+    truncated.push('…');
+    truncated
+}
+
+/// Fills the embed fields every platform shares.
+pub fn base_embed(channel: &channels::Model, post: &Post, embed: &mut CreateEmbed) {
+    let title = post
+        .title
+        .as_deref()
+        .or(post.text.as_deref())
+        .unwrap_or(&channel.ch_description);
+
+    embed
+        .title(truncate(title, EMBED_TITLE_LEN))
+        .colour((149, 66, 245));
+
+    // Discord rejects embeds with an empty URL
+    if !post.url.is_empty() {
+        embed.url(&post.url);
+    }
+
+    if post.title.is_some() {
+        if let Some(text) = &post.text {
+            embed.description(truncate(text, EMBED_DESCRIPTION_LEN));
+        }
+    }
+
+    if let Some(author) = &post.author {
+        embed.author(|a| a.name(author));
+    }
+
+    if let Some(thumbnail) = &post.thumbnail {
+        embed.image(thumbnail);
+    }
+
+    if let Some(published) = post.published {
+        embed.timestamp(published);
+    }
 }
 
 async fn fetch_rss(
     client: &Client,
+    cache: &HttpCache,
     url: Cow<'_, str>,
 ) -> Result<Feed, Box<dyn Error + Send + Sync>> {
-    let bytes = client
-        .get(url.as_ref())
-        .send()
-        .await?
-        .error_for_status()?
-        .bytes()
-        .await?;
-
-    feed_rs::parser::parse(bytes.as_ref()).map_err(|e| e.into())
+    let bytes = cache.get(client, url.as_ref()).await?;
+
+    feed_rs::parser::parse(bytes.as_slice()).map_err(|e| e.into())
 }
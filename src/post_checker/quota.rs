@@ -0,0 +1,138 @@
+use chrono::{DateTime, Duration as ChronoDuration, FixedOffset, NaiveDate, Utc};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Spending is only projected once this much of the day has passed.
+const MIN_ELAPSED: Duration = Duration::from_secs(15 * 60);
+
+/// Estimated spending of a daily API quota.
+///
+/// The estimate lives in memory only, so a restart starts the day from zero.
+pub struct Quota {
+    budget: u32,
+    usage: Mutex<Usage>,
+}
+
+struct Usage {
+    day: NaiveDate,
+    spent: u32,
+    /// Set when the API reported the quota exceeded before our estimate did.
+    exhausted: bool,
+}
+
+impl Quota {
+    pub fn new(budget: u32) -> Quota {
+        Quota {
+            budget,
+            usage: Mutex::new(Usage {
+                day: quota_day(Utc::now()),
+                spent: 0,
+                exhausted: false,
+            }),
+        }
+    }
+
+    pub fn budget(&self) -> u32 {
+        self.budget
+    }
+
+    /// Runs `f` on today's usage, starting over when the quota was reset.
+    fn with_usage<T>(&self, f: impl FnOnce(&mut Usage) -> T) -> T {
+        let mut usage = self.usage.lock().unwrap();
+        let today = quota_day(Utc::now());
+
+        if usage.day != today {
+            *usage = Usage {
+                day: today,
+                spent: 0,
+                exhausted: false,
+            };
+        }
+
+        f(&mut usage)
+    }
+
+    /// Books `units` if they fit in what is left of today's budget.
+    pub fn try_spend(&self, units: u32) -> bool {
+        self.with_usage(|usage| {
+            if usage.exhausted || usage.spent + units > self.budget {
+                return false;
+            }
+
+            usage.spent += units;
+            true
+        })
+    }
+
+    /// Marks the quota as used up until it resets.
+    pub fn exhaust(&self) {
+        self.with_usage(|usage| usage.exhausted = true);
+    }
+
+    pub fn spent(&self) -> u32 {
+        self.with_usage(|usage| usage.spent)
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.with_usage(|usage| usage.exhausted || usage.spent >= self.budget)
+    }
+
+    /// When the quota is reset next.
+    pub fn resets_at(&self) -> DateTime<Utc> {
+        day_start(quota_day(Utc::now()) + ChronoDuration::days(1))
+    }
+
+    /// Stretches a polling interval so the rest of the day stays within budget.
+    ///
+    /// Spending is assumed to continue at the average rate of the day so far.
+    pub fn pace(&self, interval: Duration) -> Duration {
+        let now = Utc::now();
+
+        let Some((spent, remaining)) = self.with_usage(|usage| {
+            let left = self.budget.saturating_sub(usage.spent);
+            (!usage.exhausted && left > 0).then_some((usage.spent, left))
+        }) else {
+            // The API isn't used until the reset, nothing left to pace
+            return interval;
+        };
+
+        let elapsed = (now - day_start(quota_day(now)))
+            .to_std()
+            .unwrap_or_default();
+        let left = (self.resets_at() - now).to_std().unwrap_or_default();
+
+        if elapsed < MIN_ELAPSED {
+            return interval;
+        }
+
+        let projected = spent as f64 * left.as_secs_f64() / elapsed.as_secs_f64();
+
+        if projected <= remaining as f64 {
+            return interval;
+        }
+
+        interval
+            .mul_f64(projected / remaining as f64)
+            .min(left.max(interval))
+    }
+}
+
+/// The YouTube quota resets at midnight Pacific Time.
+///
+/// Standard time is used all year, during daylight saving time the reset is assumed an
+/// hour after it happened, which only errs on the side of spending less.
+fn pacific() -> FixedOffset {
+    FixedOffset::west_opt(8 * 3600).unwrap()
+}
+
+fn quota_day(now: DateTime<Utc>) -> NaiveDate {
+    now.with_timezone(&pacific()).date_naive()
+}
+
+fn day_start(day: NaiveDate) -> DateTime<Utc> {
+    day.and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_local_timezone(pacific())
+        .unwrap()
+        .with_timezone(&Utc)
+}
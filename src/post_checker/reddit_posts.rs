@@ -1,24 +1,29 @@
-use entity::{channels, platforms, posts};
+use chrono::{TimeZone, Utc};
+use entity::channels;
 use percent_encoding::NON_ALPHANUMERIC;
-use poise::serenity_prelude::{ChannelId, Mentionable, ParseValue, RoleId};
+use poise::serenity_prelude::CreateEmbed;
 use reqwest::header::{HeaderMap, HeaderValue};
 use reqwest::Client;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
 use serde::Deserialize;
-use std::borrow::Cow;
 use std::error::Error;
 use std::fs;
 use std::sync::Arc;
-use tracing::{error, info};
+use std::time::Duration;
 
-use crate::commands::PlatformType;
+use super::http::HttpCache;
+use super::{base_embed, Checker, Platform, Post, Source};
 
-use super::{fetch_rss, Checker};
+pub const PLATFORM: Platform = Platform {
+    name: "Reddit",
+    description: "Reddit",
+    interval: Duration::from_secs(300),
+    create: || Box::pin(async { Some(PostChecker::new().await as Arc<dyn Checker>) }),
+};
 
 pub struct PostChecker {
     client: Client,
-    debug_mode: bool,
-    db: DatabaseConnection,
+    /// Shared by every request so Reddit's rate limit applies to all of them.
+    cache: HttpCache,
 }
 
 #[derive(Debug, Deserialize)]
@@ -26,8 +31,71 @@ struct RedditClientConfig {
     user_agent: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct Listing {
+    data: ListingData,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListingData {
+    children: Vec<Thing>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Thing {
+    data: Link,
+}
+
+#[derive(Debug, Deserialize)]
+struct Link {
+    /// Fullname (`t3_…`), the same ID the RSS feed used to report.
+    name: String,
+    title: String,
+    author: String,
+    subreddit_name_prefixed: String,
+    permalink: String,
+    link_flair_text: Option<String>,
+    score: i64,
+    created_utc: f64,
+    #[serde(default)]
+    selftext: String,
+    preview: Option<Preview>,
+    /// Set once a post is removed by its author, a moderator or the admins.
+    removed_by_category: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct About {
+    data: Subreddit,
+}
+
+#[derive(Debug, Deserialize)]
+struct Subreddit {
+    /// The icon set in the redesign, empty if there is none.
+    #[serde(default)]
+    community_icon: Option<String>,
+    /// The icon set in old Reddit.
+    #[serde(default)]
+    icon_img: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Preview {
+    images: Vec<PreviewImage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PreviewImage {
+    source: PreviewSource,
+}
+
+#[derive(Debug, Deserialize)]
+struct PreviewSource {
+    url: String,
+}
+
 impl PostChecker {
-    pub async fn new(debug_mode: bool, connection: DatabaseConnection) -> Arc<PostChecker> {
+    pub async fn new() -> Arc<PostChecker> {
         let reddit_config = serde_json::from_str::<RedditClientConfig>(
             fs::read_to_string("keys/reddit-rss.json").unwrap().as_str(),
         )
@@ -47,117 +115,127 @@ impl PostChecker {
 
         Arc::new(Self {
             client,
-            debug_mode,
-            db: connection,
+            cache: HttpCache::default(),
         })
     }
 }
 
 #[async_trait::async_trait]
 impl Checker for PostChecker {
-    fn name(&self) -> &str {
-        "Reddit"
+    async fn validate_id(&self, id: &str) -> Result<Source, String> {
+        let name = id.trim().trim_start_matches('/');
+        let name = name
+            .strip_prefix("r/")
+            .unwrap_or(name)
+            .trim_end_matches('/');
+
+        if !(2..=21).contains(&name.len())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(format!("**{id}** is not a valid subreddit name."));
+        }
+
+        Ok(Source {
+            id: name.to_owned(),
+            name: Some(format!("r/{name}")),
+        })
     }
 
-    async fn check(
-        &self,
-        ctx: impl AsRef<serenity::http::Http> + Send + Sync,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let platform_channels = platforms::Entity::find()
-            .filter(platforms::Column::PlName.eq(PlatformType::Reddit.to_string()))
-            .find_with_related(channels::Entity)
-            .all(&self.db)
-            .await?
+    async fn fetch(&self, id: &str) -> Result<Vec<Post>, Box<dyn Error + Send + Sync>> {
+        let subreddit = percent_encoding::utf8_percent_encode(id, NON_ALPHANUMERIC);
+
+        let url = format!("https://www.reddit.com/r/{subreddit}/new.json?raw_json=1");
+        let body = self.cache.get(&self.client, &url).await?;
+        let listing = serde_json::from_slice::<Listing>(&body)?;
+
+        Ok(listing
+            .data
+            .children
             .into_iter()
-            .flat_map(|(_, r)| r)
-            .collect::<Vec<_>>();
+            .map(|thing| {
+                let link = thing.data;
+
+                Post {
+                    id: link.name,
+                    url: format!("https://www.reddit.com{}", link.permalink),
+                    title: Some(link.title),
+                    text: Some(link.selftext).filter(|text| !text.is_empty()),
+                    author: Some(format!("/u/{}", link.author)),
+                    source: Some(link.subreddit_name_prefixed),
+                    category: link.link_flair_text.filter(|flair| !flair.is_empty()),
+                    thumbnail: link
+                        .preview
+                        .and_then(|preview| preview.images.into_iter().next())
+                        .map(|image| image.source.url),
+                    published: Utc.timestamp_opt(link.created_utc as i64, 0).single(),
+                    score: Some(link.score),
+                    ..Default::default()
+                }
+            })
+            .collect())
+    }
 
-        for channel in platform_channels {
-            let subreddit =
-                percent_encoding::utf8_percent_encode(&channel.ch_name, NON_ALPHANUMERIC);
+    async fn removed(&self, ids: &[String]) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
 
-            let res = fetch_rss(
-                &self.client,
-                Cow::Owned(format!("https://reddit.com/r/{}/new.rss", subreddit)),
-            )
-            .await;
+        let url = format!(
+            "https://www.reddit.com/by_id/{}.json?raw_json=1",
+            ids.join(",")
+        );
+        let body = self.cache.get(&self.client, &url).await?;
+        let listing = serde_json::from_slice::<Listing>(&body)?;
 
-            if let Err(ref err) = res {
-                error!("Reddit fetch error: {:?}", err);
-                continue;
-            }
+        let remaining = listing
+            .data
+            .children
+            .into_iter()
+            .filter(|thing| thing.data.removed_by_category.is_none())
+            .map(|thing| thing.data.name)
+            .collect::<Vec<_>>();
 
-            let feed = res.unwrap();
+        Ok(ids
+            .iter()
+            .filter(|id| !remaining.contains(id))
+            .cloned()
+            .collect())
+    }
 
-            for entry in feed.entries {
-                let id = entry.id;
+    async fn avatar(&self, id: &str) -> Result<Option<String>, Box<dyn Error + Send + Sync>> {
+        let subreddit = percent_encoding::utf8_percent_encode(id, NON_ALPHANUMERIC);
 
-                let matches = posts::Entity::find()
-                    .filter(posts::Column::PoName.eq(id.clone()))
-                    .one(&self.db)
-                    .await;
+        let url = format!("https://www.reddit.com/r/{subreddit}/about.json?raw_json=1");
+        let body = self.cache.get(&self.client, &url).await?;
+        let about = serde_json::from_slice::<About>(&body)?;
 
-                if let Err(ref err) = matches {
-                    error!("DB error checking for matches: {err}");
-                    continue;
-                }
+        Ok([about.data.community_icon, about.data.icon_img]
+            .into_iter()
+            .flatten()
+            .find(|icon| !icon.is_empty()))
+    }
 
-                if let Ok(Some(_)) = matches {
-                    continue;
-                }
+    fn announcement(&self, channel: &channels::Model, post: &Post, mention: &str) -> String {
+        let author = post.author.as_deref().unwrap_or("<unknown>");
+        let subreddit = post.source.as_ref().unwrap_or(&channel.ch_description);
+        let url = &post.url;
 
-                info!("New post: {}, debug mode: {}", id, self.debug_mode);
+        format!("Hey {mention}, user **{author}** has posted on **{subreddit}**!\n{url}")
+    }
 
-                let post = posts::ActiveModel {
-                    po_ch_id: Set(channel.ch_id),
-                    po_name: Set(id.to_owned()),
-                    po_time_added: Set(chrono::Utc::now().naive_utc()),
-                    ..Default::default()
-                };
-
-                post.save(&self.db).await?;
-
-                let mention = if let Some(role) = channel.ch_role_mention_id {
-                    RoleId::from(role as u64).mention().to_string()
-                } else {
-                    "@everyone".to_owned()
-                };
-
-                let author = entry
-                    .authors
-                    .first()
-                    .map_or("<unknown>", |author| &author.name);
-
-                let url = entry.links.first().map_or("", |link| &link.href);
-
-                let subreddit = entry
-                    .categories
-                    .first()
-                    .and_then(|cat| cat.label.as_ref())
-                    .unwrap_or(&channel.ch_description);
-
-                let text = format!(
-                    "Hey {mention}, user **{author}** has posted on **{subreddit}**!\n{url}"
-                );
-
-                ChannelId::from(channel.ch_discord_channel_id as u64)
-                    .send_message(&ctx, |msg| {
-                        if !self.debug_mode && channel.ch_mention_flag {
-                            msg.content(text).allowed_mentions(|am| {
-                                if let Some(role_id) = channel.ch_role_mention_id {
-                                    am.empty_parse().roles(vec![role_id as u64])
-                                } else {
-                                    am.empty_parse().parse(ParseValue::Everyone)
-                                }
-                            })
-                        } else {
-                            msg.content(text).allowed_mentions(|am| am.empty_parse())
-                        }
-                    })
-                    .await?;
-            }
+    fn embed(&self, channel: &channels::Model, post: &Post, embed: &mut CreateEmbed) {
+        base_embed(channel, post, embed);
+
+        if let Some(subreddit) = &post.source {
+            embed.field("Subreddit", subreddit, true);
         }
 
-        Ok(())
+        if let Some(flair) = &post.category {
+            embed.field("Flair", flair, true);
+        }
+
+        if let Some(score) = post.score {
+            embed.field("Score", score, true);
+        }
     }
 }
@@ -0,0 +1,906 @@
+use entity::{channels, filters, guilds, platforms, posts, video_kinds};
+use migration::OnConflict;
+use poise::serenity_prelude::{
+    ChannelId, CreateMessage, EditMessage, Http, Mentionable, MessageId, ParseValue, RoleId,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tracing::{error, info};
+
+use super::delivery::{BotDelivery, Delivery, Sender, WebhookDelivery};
+use super::filter::Rules;
+use super::forum::{self, Tag};
+use super::http::RetryAfter;
+use super::scheduler::{Schedule, SourceKey};
+use super::{
+    template, truncate, Checker, DeliveryMethod, Platform, Post, PostKind, RemovalAction, PLATFORMS,
+};
+
+/// Maximum length of a Discord message.
+const MESSAGE_LEN: usize = 2000;
+
+/// How often the scheduler looks for links that are due.
+const TICK: Duration = Duration::from_secs(10);
+
+/// Maximum number of links checked at the same time.
+const MAX_IN_FLIGHT: usize = 8;
+
+/// How many of a link's latest announcements are checked for removal on every sweep.
+const REMOVAL_WINDOW: u64 = 25;
+
+/// How often the announcements of a source are checked for removal upstream.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Longest a crosspost may wait out Discord's rate limit before it's left to a later check.
+const PUBLISH_WAIT: Duration = Duration::from_secs(30);
+
+/// How long a source is deferred when publishing its announcements was rate limited.
+const PUBLISH_RETRY: Duration = Duration::from_secs(10 * 60);
+
+/// How long the kind and tags of an announcement channel are reused before looking again.
+const FORUM_REFRESH: Duration = Duration::from_secs(60 * 60);
+
+/// The tags of a forum channel, `None` for any other channel.
+type ForumTags = Option<Arc<[Tag]>>;
+
+/// Owns one checker instance per registered platform.
+pub struct Registry {
+    checkers: Vec<(&'static Platform, Arc<dyn Checker>)>,
+    debug_mode: bool,
+    db: DatabaseConnection,
+    schedule: Mutex<Schedule>,
+    permits: Semaphore,
+    bot: BotDelivery,
+    webhook: WebhookDelivery,
+    /// Icons of the sources announced through webhooks, looked up once per run.
+    avatars: Mutex<HashMap<SourceKey, Option<String>>>,
+    /// When each source was last checked for removed posts.
+    swept: Mutex<HashMap<SourceKey, Instant>>,
+    /// Whether the channels links announce in are forums, and when that was looked up.
+    forums: Mutex<HashMap<ChannelId, (Instant, ForumTags)>>,
+}
+
+impl Registry {
+    pub async fn new(debug_mode: bool, connection: DatabaseConnection) -> Arc<Registry> {
+        let mut checkers = Vec::with_capacity(PLATFORMS.len());
+
+        for platform in PLATFORMS {
+            if let Some(checker) = (platform.create)().await {
+                checkers.push((platform, checker));
+            }
+        }
+
+        Arc::new(Self {
+            checkers,
+            debug_mode,
+            db: connection,
+            schedule: Mutex::new(Schedule::default()),
+            permits: Semaphore::new(MAX_IN_FLIGHT),
+            bot: BotDelivery,
+            webhook: WebhookDelivery::default(),
+            avatars: Mutex::new(HashMap::new()),
+            swept: Mutex::new(HashMap::new()),
+            forums: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn checker(&self, platform: &Platform) -> Option<&Arc<dyn Checker>> {
+        self.checkers
+            .iter()
+            .find(|(p, _)| p.name == platform.name)
+            .map(|(_, checker)| checker)
+    }
+
+    /// Makes sure every registered platform has a row in the `platforms` table.
+    pub async fn seed(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let rows = self
+            .checkers
+            .iter()
+            .map(|(platform, _)| platforms::ActiveModel {
+                pl_name: Set(platform.name.to_owned()),
+                pl_description: Set(platform.description.to_owned()),
+                ..Default::default()
+            });
+
+        platforms::Entity::insert_many(rows)
+            .on_conflict(
+                OnConflict::column(platforms::Column::PlName)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Spawns the scheduler checking every source whenever it is due.
+    pub fn spawn(self: &Arc<Self>, ctx: Arc<Http>) {
+        let registry = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = registry.dispatch(&ctx).await {
+                    error!("Failed to schedule checks: {:?}", err);
+                }
+
+                tokio::time::sleep(TICK).await;
+            }
+        });
+    }
+
+    /// Starts a check for every source that is due, at most `MAX_IN_FLIGHT` run at once.
+    ///
+    /// Links following the same source share a single fetch.
+    async fn dispatch(
+        self: &Arc<Self>,
+        ctx: &Arc<Http>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let links = channels::Entity::find()
+            .find_also_related(platforms::Entity)
+            .all(&self.db)
+            .await?;
+
+        let mut sources = HashMap::<SourceKey, Vec<channels::Model>>::new();
+
+        let active = links
+            .iter()
+            .filter(|(channel, platform)| channel.ch_enabled && platform.is_some());
+
+        for (channel, _) in active {
+            sources
+                .entry((channel.ch_pl_id, channel.ch_name.clone()))
+                .or_default()
+                .push(channel.clone());
+        }
+
+        let platform_names = links
+            .into_iter()
+            .filter_map(|(_, platform)| platform)
+            .map(|platform| (platform.pl_id, platform.pl_name))
+            .collect::<HashMap<_, _>>();
+
+        let now = Instant::now();
+        let mut schedule = self.schedule.lock().unwrap();
+
+        schedule.retain(|key| sources.contains_key(key));
+        self.swept
+            .lock()
+            .unwrap()
+            .retain(|key, _| sources.contains_key(key));
+
+        for (key, channels) in sources {
+            let Some(&(platform, ref checker)) = platform_names
+                .get(&key.0)
+                .and_then(|name| self.checkers.iter().find(|(p, _)| p.name == name))
+            else {
+                continue;
+            };
+
+            if !schedule.try_claim(&key, now) {
+                continue;
+            }
+
+            // The most eager link sets the pace for the source
+            let interval = channels
+                .iter()
+                .map(|channel| {
+                    channel
+                        .ch_interval
+                        .map_or(platform.interval, |secs| Duration::from_secs(secs as u64))
+                })
+                .min()
+                .unwrap_or(platform.interval);
+            let interval = checker.pace(interval);
+
+            let registry = self.clone();
+            let checker = checker.clone();
+            let ctx = ctx.clone();
+
+            tokio::spawn(async move {
+                let res = match registry.permits.acquire().await {
+                    Ok(_permit) => {
+                        registry
+                            .poll(platform, checker.as_ref(), &ctx, &key.1, &channels)
+                            .await
+                    }
+                    Err(err) => Err(err.into()),
+                };
+
+                let mut schedule = registry.schedule.lock().unwrap();
+
+                let retry = res
+                    .as_ref()
+                    .err()
+                    .and_then(|err| err.downcast_ref::<RetryAfter>());
+
+                if let Some(retry) = retry {
+                    info!("Deferring {} source {}: {}", platform.name, key.1, retry);
+                    schedule.defer(&key, retry.0.max(interval));
+                    return;
+                }
+
+                schedule.finish(&key, interval, res.is_ok());
+
+                if let Err(err) = res {
+                    error!(
+                        "Failed to check {} source {} ({} failures in a row): {:?}",
+                        platform.name,
+                        key.1,
+                        schedule.failures(&key),
+                        err
+                    );
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Fetches a source once and hands its entries to every link following it.
+    async fn poll(
+        &self,
+        platform: &Platform,
+        checker: &dyn Checker,
+        ctx: &Http,
+        source: &str,
+        channels: &[channels::Model],
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let entries = checker.fetch(source).await?;
+        let mut deferred = None;
+
+        for channel in channels {
+            // One link failing to announce shouldn't hold back the others
+            let Err(err) = self
+                .check(platform, checker, ctx, channel, entries.clone())
+                .await
+            else {
+                continue;
+            };
+
+            match err.downcast::<RetryAfter>() {
+                Ok(retry) => deferred = deferred.max(Some(retry.0)),
+                Err(err) => error!(
+                    "Failed to announce {} posts of {} in {}: {:?}",
+                    platform.name, source, channel.ch_discord_channel_id, err
+                ),
+            }
+        }
+
+        if let Some(channel) = channels.first().filter(|channel| self.sweep_due(channel)) {
+            if let Err(err) = self.sweep(ctx, checker, channels).await {
+                error!(
+                    "{} removal check error for {}: {:?}",
+                    platform.name, channel.ch_name, err
+                );
+            }
+        }
+
+        // Announcements that couldn't be published yet are retried once the source is due again
+        match deferred {
+            Some(wait) => Err(RetryAfter(wait).into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Fills in the defaults of the link's guild where the link leaves a setting unset.
+    pub async fn with_guild_defaults(
+        &self,
+        channel: &channels::Model,
+    ) -> Result<channels::Model, Box<dyn Error + Send + Sync>> {
+        let mut channel = channel.clone();
+
+        let Some(guild_id) = channel.ch_gu_id else {
+            return Ok(channel);
+        };
+
+        if let Some(guild) = guilds::Entity::find_by_id(guild_id).one(&self.db).await? {
+            channel.ch_template = channel.ch_template.or(guild.gu_template);
+            channel.ch_role_mention_id = channel.ch_role_mention_id.or(guild.gu_role_mention_id);
+        }
+
+        Ok(channel)
+    }
+
+    /// Announces the entries of a link's source it hasn't seen yet.
+    async fn check(
+        &self,
+        platform: &Platform,
+        checker: &dyn Checker,
+        ctx: &Http,
+        channel: &channels::Model,
+        entries: Vec<Post>,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let channel = &self.with_guild_defaults(channel).await?;
+        let rules = Rules::new(&channel.find_related(filters::Entity).all(&self.db).await?);
+
+        let kinds = if entries.iter().any(|post| post.video.is_some()) {
+            channel
+                .find_related(video_kinds::Entity)
+                .all(&self.db)
+                .await?
+        } else {
+            Vec::new()
+        };
+
+        let wanted = |post: &Post| {
+            wants(channel, post)
+                && rules.allows(post)
+                && video_settings(&kinds, post).is_none_or(|settings| settings.vk_enabled)
+        };
+
+        if !channel.ch_primed {
+            self.prime(channel, &entries, wanted).await?;
+        }
+
+        // Filtered posts aren't recorded, a post can still pass once its score grows
+        let entries = entries.into_iter().filter(|post| wanted(post));
+
+        // Entries come newest first, announce them in the order they were posted
+        for mut post in entries.rev() {
+            let matches = posts::Entity::find()
+                .filter(posts::Column::PoPlId.eq(channel.ch_pl_id))
+                .filter(posts::Column::PoName.eq(post.id.clone()))
+                .filter(posts::Column::PoChId.eq(channel.ch_id))
+                .one(&self.db)
+                .await;
+
+            let matches = match matches {
+                Ok(matches) => matches,
+                Err(err) => {
+                    error!("DB error checking for matches: {err}");
+                    continue;
+                }
+            };
+
+            let channel = match video_settings(&kinds, &post) {
+                Some(settings) => with_video_settings(channel, settings),
+                None => channel.clone(),
+            };
+
+            if let Some(row) = matches {
+                self.update(ctx, checker, &channel, &post, row).await?;
+                continue;
+            }
+
+            info!("New post: {}, debug mode: {}", post.id, self.debug_mode);
+
+            // Resolved before the post is recorded, so a failed lookup is retried on the next check
+            let forum = self
+                .forum(ctx, ChannelId::from(channel.ch_discord_channel_id as u64))
+                .await?;
+
+            let model = posts::ActiveModel {
+                po_ch_id: Set(channel.ch_id),
+                po_pl_id: Set(channel.ch_pl_id),
+                po_name: Set(post.id.to_owned()),
+                po_time_added: Set(chrono::Utc::now().naive_utc()),
+                po_state: Set(post.live.map(|state| state.as_str().to_owned())),
+                ..Default::default()
+            };
+
+            let mut model = model.save(&self.db).await?;
+
+            // Missing details only make the announcement less rich, don't drop it
+            if let Err(err) = checker.enrich(&mut post).await {
+                error!("{} enrich error: {:?}", platform.name, err);
+            }
+
+            let (channel_id, message_id, delivery) = self
+                .announce(ctx, checker, &channel, &post, forum.as_deref())
+                .await?;
+
+            model.po_message_id = Set(Some(message_id.0 as i64));
+            model.po_delivery = Set(delivery.as_str().to_owned());
+            model.po_discord_channel_id = Set(Some(channel_id.0 as i64));
+            model.po_publish_pending = Set(channel.ch_crosspost);
+            model.update(&self.db).await?;
+        }
+
+        self.publish(ctx, channel).await
+    }
+
+    /// Records the current entries of a newly linked or resumed source as already announced.
+    ///
+    /// The newest `ch_backfill` wanted entries of a new link are left out, so the ongoing
+    /// check announces them. Links that have announced before skip their whole backlog.
+    async fn prime(
+        &self,
+        channel: &channels::Model,
+        entries: &[Post],
+        wanted: impl Fn(&Post) -> bool,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let resumed = channel
+            .find_related(posts::Entity)
+            .one(&self.db)
+            .await?
+            .is_some();
+
+        let mut backfill = if resumed {
+            0
+        } else {
+            channel.ch_backfill.max(0) as usize
+        };
+
+        info!(
+            "Priming channel {} with {} entries, backfill: {}",
+            channel.ch_name,
+            entries.len(),
+            backfill
+        );
+
+        let seen = entries
+            .iter()
+            .filter(|post| {
+                if backfill > 0 && wanted(post) {
+                    backfill -= 1;
+                    false
+                } else {
+                    true
+                }
+            })
+            .map(|post| posts::ActiveModel {
+                po_ch_id: Set(channel.ch_id),
+                po_pl_id: Set(channel.ch_pl_id),
+                po_name: Set(post.id.to_owned()),
+                po_time_added: Set(chrono::Utc::now().naive_utc()),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+
+        if !seen.is_empty() {
+            posts::Entity::insert_many(seen)
+                .on_conflict(
+                    OnConflict::columns([
+                        posts::Column::PoPlId,
+                        posts::Column::PoName,
+                        posts::Column::PoChId,
+                    ])
+                    .do_nothing()
+                    .to_owned(),
+                )
+                .exec_without_returning(&self.db)
+                .await?;
+        }
+
+        channels::ActiveModel {
+            ch_id: Set(channel.ch_id),
+            ch_primed: Set(true),
+            ..Default::default()
+        }
+        .update(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Crossposts the announcements of a link that still have to reach following servers.
+    ///
+    /// Discord allows few crossposts per channel and hour, waiting out its rate limit is left
+    /// to the scheduler instead of holding up the check.
+    async fn publish(
+        &self,
+        ctx: &Http,
+        channel: &channels::Model,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let rows = posts::Entity::find()
+            .filter(posts::Column::PoChId.eq(channel.ch_id))
+            .filter(posts::Column::PoPublishPending.eq(true))
+            .order_by_asc(posts::Column::PoId)
+            .all(&self.db)
+            .await?;
+
+        for row in rows {
+            if let Some(message_id) = row.po_message_id {
+                let channel_id = announced_in(channel, &row);
+                let crosspost = channel_id.crosspost(ctx, message_id as u64);
+
+                match tokio::time::timeout(PUBLISH_WAIT, crosspost).await {
+                    Ok(Ok(_)) => {}
+                    // Not an announcement channel, or the message is gone, retrying won't help
+                    Ok(Err(err)) => error!(
+                        "Failed to publish announcement of {}: {:?}",
+                        row.po_name, err
+                    ),
+                    Err(_) => return Err(RetryAfter(PUBLISH_RETRY).into()),
+                }
+            }
+
+            let mut row: posts::ActiveModel = row.into();
+            row.po_publish_pending = Set(false);
+            row.update(&self.db).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether the links of `channel`'s source are due for a removal check, books it if so.
+    fn sweep_due(&self, channel: &channels::Model) -> bool {
+        let now = Instant::now();
+        let mut swept = self.swept.lock().unwrap();
+
+        let key = (channel.ch_pl_id, channel.ch_name.clone());
+
+        if swept
+            .get(&key)
+            .is_some_and(|last| now.duration_since(*last) < SWEEP_INTERVAL)
+        {
+            return false;
+        }
+
+        swept.insert(key, now);
+        true
+    }
+
+    /// Marks or deletes the announcements of recent posts that were removed upstream.
+    ///
+    /// The links of a source share a single lookup.
+    async fn sweep(
+        &self,
+        ctx: &Http,
+        checker: &dyn Checker,
+        channels: &[channels::Model],
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut links = Vec::new();
+
+        for channel in channels {
+            let action = RemovalAction::from_column(&channel.ch_on_removal);
+
+            if action == RemovalAction::Ignore {
+                continue;
+            }
+
+            let rows = posts::Entity::find()
+                .filter(posts::Column::PoChId.eq(channel.ch_id))
+                .filter(posts::Column::PoMessageId.is_not_null())
+                .filter(posts::Column::PoRemoved.eq(false))
+                .order_by_desc(posts::Column::PoId)
+                .limit(REMOVAL_WINDOW)
+                .all(&self.db)
+                .await?;
+
+            links.push((channel, action, rows));
+        }
+
+        let mut ids = links
+            .iter()
+            .flat_map(|(_, _, rows)| rows.iter().map(|row| row.po_name.clone()))
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids.dedup();
+
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let removed = checker.removed(&ids).await?;
+
+        for (channel, action, rows) in links {
+            self.retract(ctx, channel, action, rows, &removed).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Applies a link's removal action to the announcements of posts that were removed.
+    async fn retract(
+        &self,
+        ctx: &Http,
+        channel: &channels::Model,
+        action: RemovalAction,
+        rows: Vec<posts::Model>,
+        removed: &[String],
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        for row in rows {
+            let Some(message_id) = row.po_message_id.filter(|_| removed.contains(&row.po_name))
+            else {
+                continue;
+            };
+
+            info!(
+                "Post {} was removed, {:?} its announcement",
+                row.po_name, action
+            );
+
+            let channel_id = announced_in(channel, &row);
+            let message_id = MessageId::from(message_id as u64);
+            let delivery = self.delivery(DeliveryMethod::from_column(&row.po_delivery));
+
+            // The announcement may already be gone, the post is still marked as handled
+            let res = match action {
+                RemovalAction::Note => match channel_id.message(ctx, message_id).await {
+                    Ok(message) => {
+                        let text =
+                            truncate(&format!("**[removed]** {}", message.content), MESSAGE_LEN);
+
+                        let mut edit = EditMessage::default();
+                        edit.content(text);
+
+                        delivery.edit(ctx, channel_id, message_id, edit).await
+                    }
+                    Err(err) => Err(err.into()),
+                },
+                // Forum threads share their ID with the starter message, remove the whole thread
+                RemovalAction::Delete if channel_id.0 == message_id.0 => {
+                    channel_id.delete(ctx).await.map(|_| ()).map_err(Into::into)
+                }
+                RemovalAction::Delete => delivery.delete(ctx, channel_id, message_id).await,
+                RemovalAction::Ignore => Ok(()),
+            };
+
+            if let Err(err) = res {
+                error!(
+                    "Failed to retract announcement of {}: {:?}",
+                    row.po_name, err
+                );
+            }
+
+            let mut row: posts::ActiveModel = row.into();
+            row.po_removed = Set(true);
+            row.update(&self.db).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Edits the announcement of a post whose broadcast state changed since it was sent.
+    async fn update(
+        &self,
+        ctx: &Http,
+        checker: &dyn Checker,
+        channel: &channels::Model,
+        post: &Post,
+        row: posts::Model,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let state = post.live.map(|state| state.as_str());
+
+        if state.is_none() || row.po_state.as_deref() == state {
+            return Ok(());
+        }
+
+        info!("Post {} changed state to {:?}", post.id, state);
+
+        // Posts recorded while priming were never announced
+        if let Some(message_id) = row.po_message_id {
+            let mut edit = EditMessage::default();
+
+            if channel.ch_embed {
+                edit.embed(|e| {
+                    checker.embed(channel, post, e);
+                    e
+                });
+            }
+
+            edit.content(content(checker, channel, post, &mention(channel)));
+
+            let res = self
+                .delivery(DeliveryMethod::from_column(&row.po_delivery))
+                .edit(
+                    ctx,
+                    announced_in(channel, &row),
+                    MessageId::from(message_id as u64),
+                    edit,
+                )
+                .await;
+
+            // The announcement may be gone, the new state is still recorded so it isn't retried
+            if let Err(err) = res {
+                error!(
+                    "Failed to update announcement of {}: {:?}",
+                    row.po_name, err
+                );
+            }
+        }
+
+        let mut row: posts::ActiveModel = row.into();
+        row.po_state = Set(state.map(str::to_owned));
+        row.update(&self.db).await?;
+
+        Ok(())
+    }
+
+    /// Sends the announcement of `post`, as a new thread if the link targets a forum channel.
+    async fn announce(
+        &self,
+        ctx: &Http,
+        checker: &dyn Checker,
+        channel: &channels::Model,
+        post: &Post,
+        forum: Option<&[Tag]>,
+    ) -> Result<(ChannelId, MessageId, DeliveryMethod), Box<dyn Error + Send + Sync>> {
+        let target = ChannelId::from(channel.ch_discord_channel_id as u64);
+
+        let mut msg = CreateMessage::default();
+        self.compose(&mut msg, checker, channel, post);
+
+        // Forum threads are always opened by the bot
+        if let Some(tags) = forum {
+            let (channel_id, message_id) =
+                forum::create_thread(ctx, target, tags, post, msg).await?;
+
+            return Ok((channel_id, message_id, DeliveryMethod::Bot));
+        }
+
+        let method = DeliveryMethod::from_column(&channel.ch_delivery);
+
+        let sender = Sender {
+            name: channel.ch_description.clone(),
+            avatar: match method {
+                DeliveryMethod::Webhook => self.avatar(checker, channel).await,
+                DeliveryMethod::Bot => None,
+            },
+        };
+
+        let (channel_id, message_id) = self
+            .delivery(method)
+            .send(ctx, target, &sender, msg)
+            .await?;
+
+        Ok((channel_id, message_id, method))
+    }
+
+    fn delivery(&self, method: DeliveryMethod) -> &dyn Delivery {
+        match method {
+            DeliveryMethod::Bot => &self.bot,
+            DeliveryMethod::Webhook => &self.webhook,
+        }
+    }
+
+    /// The tags of `channel_id` if it's a forum channel, looked up once per `FORUM_REFRESH`.
+    async fn forum(
+        &self,
+        ctx: &Http,
+        channel_id: ChannelId,
+    ) -> Result<ForumTags, Box<dyn Error + Send + Sync>> {
+        if let Some((fetched, tags)) = self.forums.lock().unwrap().get(&channel_id) {
+            if fetched.elapsed() < FORUM_REFRESH {
+                return Ok(tags.clone());
+            }
+        }
+
+        let tags = forum::tags(ctx, channel_id).await?.map(Arc::from);
+
+        self.forums
+            .lock()
+            .unwrap()
+            .insert(channel_id, (Instant::now(), tags.clone()));
+
+        Ok(tags)
+    }
+
+    /// The icon of the link's source, a missing icon doesn't hold up the announcement.
+    async fn avatar(&self, checker: &dyn Checker, channel: &channels::Model) -> Option<String> {
+        let key = (channel.ch_pl_id, channel.ch_name.clone());
+
+        if let Some(avatar) = self.avatars.lock().unwrap().get(&key) {
+            return avatar.clone();
+        }
+
+        let avatar = match checker.avatar(&channel.ch_name).await {
+            Ok(avatar) => avatar,
+            Err(err) => {
+                error!(
+                    "Failed to look up the icon of {}: {:?}",
+                    channel.ch_name, err
+                );
+                return None;
+            }
+        };
+
+        self.avatars.lock().unwrap().insert(key, avatar.clone());
+
+        avatar
+    }
+
+    /// Fills in the announcement of `post`.
+    fn compose<'a, 'b>(
+        &self,
+        msg: &'b mut CreateMessage<'a>,
+        checker: &dyn Checker,
+        channel: &channels::Model,
+        post: &Post,
+    ) -> &'b mut CreateMessage<'a> {
+        let text = content(checker, channel, post, &mention(channel));
+
+        if channel.ch_embed {
+            msg.embed(|e| {
+                checker.embed(channel, post, e);
+                e
+            });
+        }
+
+        if !self.debug_mode && channel.ch_mention_flag {
+            msg.content(text).allowed_mentions(|am| {
+                if let Some(role_id) = channel.ch_role_mention_id {
+                    am.empty_parse().roles(vec![role_id as u64])
+                } else {
+                    am.empty_parse().parse(ParseValue::Everyone)
+                }
+            })
+        } else {
+            msg.content(text).allowed_mentions(|am| am.empty_parse())
+        }
+    }
+}
+
+/// The mention placed in a link's announcements.
+pub fn mention(channel: &channels::Model) -> String {
+    if let Some(role) = channel.ch_role_mention_id {
+        RoleId::from(role as u64).mention().to_string()
+    } else {
+        "@everyone".to_owned()
+    }
+}
+
+/// The message text announcing `post`, from the link's template if it has one.
+pub fn content(
+    checker: &dyn Checker,
+    channel: &channels::Model,
+    post: &Post,
+    mention: &str,
+) -> String {
+    let text = if let Some(template) = &channel.ch_template {
+        template::render(template, channel, post, mention)
+    } else if channel.ch_embed {
+        // Embeds carry the details themselves, the message only holds the mention
+        mention.to_owned()
+    } else {
+        checker.announcement(channel, post, mention)
+    };
+
+    truncate(&text, MESSAGE_LEN)
+}
+
+/// The Discord channel a post was announced in, links may have moved since.
+fn announced_in(channel: &channels::Model, row: &posts::Model) -> ChannelId {
+    ChannelId::from(
+        row.po_discord_channel_id
+            .unwrap_or(channel.ch_discord_channel_id) as u64,
+    )
+}
+
+/// The link's settings for the type of video `post` is, if it has any.
+fn video_settings<'a>(
+    kinds: &'a [video_kinds::Model],
+    post: &Post,
+) -> Option<&'a video_kinds::Model> {
+    let kind = post.video?;
+
+    kinds
+        .iter()
+        .find(|settings| settings.vk_kind == kind.as_str())
+}
+
+/// Applies the ping choice and template of a video type on top of the link's own.
+fn with_video_settings(
+    channel: &channels::Model,
+    settings: &video_kinds::Model,
+) -> channels::Model {
+    let mut channel = channel.clone();
+
+    if let Some(mention_flag) = settings.vk_mention_flag {
+        channel.ch_mention_flag = mention_flag;
+    }
+
+    if settings.vk_template.is_some() {
+        channel.ch_template = settings.vk_template.clone();
+    }
+
+    channel
+}
+
+/// Whether the link's options allow announcing this kind of post.
+pub(super) fn wants(channel: &channels::Model, post: &Post) -> bool {
+    match post.kind {
+        PostKind::Original => true,
+        PostKind::Reply => channel.ch_show_replies,
+        PostKind::Repost => channel.ch_show_reposts,
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Longest a failing source is left alone before it is tried again.
+const MAX_BACKOFF: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// A source as followed by links, its platform ID and `channels.ch_name`.
+pub type SourceKey = (i64, String);
+
+/// When each source is due to be checked next.
+#[derive(Default)]
+pub struct Schedule {
+    slots: HashMap<SourceKey, Slot>,
+}
+
+struct Slot {
+    due: Instant,
+    /// Consecutive failed checks, doubles the wait each time.
+    failures: u32,
+    running: bool,
+}
+
+impl Schedule {
+    /// Forgets sources no link follows anymore.
+    pub fn retain(&mut self, mut keep: impl FnMut(&SourceKey) -> bool) {
+        self.slots.retain(|key, _| keep(key));
+    }
+
+    /// Marks a source as running if it is due, new sources are due right away.
+    pub fn try_claim(&mut self, key: &SourceKey, now: Instant) -> bool {
+        let slot = self.slots.entry(key.clone()).or_insert(Slot {
+            due: now,
+            failures: 0,
+            running: false,
+        });
+
+        if slot.running || slot.due > now {
+            return false;
+        }
+
+        slot.running = true;
+        true
+    }
+
+    /// Schedules the next check of a source after one finished.
+    pub fn finish(&mut self, key: &SourceKey, interval: Duration, success: bool) {
+        let Some(slot) = self.slots.get_mut(key) else {
+            return;
+        };
+
+        slot.failures = if success { 0 } else { slot.failures + 1 };
+        slot.running = false;
+        slot.due = Instant::now() + backoff(interval, slot.failures);
+    }
+
+    /// Schedules the next check of a source a host asked us to wait for, not a failure.
+    pub fn defer(&mut self, key: &SourceKey, delay: Duration) {
+        let Some(slot) = self.slots.get_mut(key) else {
+            return;
+        };
+
+        slot.running = false;
+        slot.due = Instant::now() + delay;
+    }
+
+    /// Consecutive failures of a source, for logging.
+    pub fn failures(&self, key: &SourceKey) -> u32 {
+        self.slots.get(key).map_or(0, |slot| slot.failures)
+    }
+}
+
+fn backoff(interval: Duration, failures: u32) -> Duration {
+    let factor = 1u32 << failures.min(16);
+
+    interval
+        .saturating_mul(factor)
+        .min(MAX_BACKOFF.max(interval))
+}
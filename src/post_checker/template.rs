@@ -0,0 +1,118 @@
+use entity::channels;
+
+use super::{LiveState, Post};
+
+/// Placeholders a template may use, shown to users when validation fails.
+pub const PLACEHOLDERS: &[&str] = &[
+    "mention", "author", "title", "text", "url", "channel", "source", "category", "status",
+];
+
+/// Maximum length of a template, leaves room for long titles in a 2000 character message.
+pub const TEMPLATE_LEN: usize = 1000;
+
+enum Segment<'a> {
+    Literal(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Splits a template into literals and placeholders, `{{` and `}}` escape braces.
+fn parse(template: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+
+    while let Some(pos) = rest.find(['{', '}']) {
+        if pos > 0 {
+            segments.push(Segment::Literal(&rest[..pos]));
+        }
+
+        let brace = &rest[pos..pos + 1];
+        rest = &rest[pos + 1..];
+
+        if let Some(escaped) = rest.strip_prefix(brace) {
+            segments.push(Segment::Literal(brace));
+            rest = escaped;
+            continue;
+        }
+
+        if brace == "}" {
+            return Err("Unmatched `}`, write `}}` for a literal brace.".to_owned());
+        }
+
+        let Some(end) = rest.find('}') else {
+            return Err("Unclosed `{`, write `{{` for a literal brace.".to_owned());
+        };
+
+        let name = &rest[..end];
+
+        if !PLACEHOLDERS.contains(&name) {
+            return Err(format!(
+                "Unknown placeholder `{{{name}}}`, available placeholders are {}.",
+                PLACEHOLDERS
+                    .iter()
+                    .map(|p| format!("`{{{p}}}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        segments.push(Segment::Placeholder(name));
+        rest = &rest[end + 1..];
+    }
+
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest));
+    }
+
+    Ok(segments)
+}
+
+/// Checks a user-supplied template, the error is shown to the user as-is.
+pub fn validate(template: &str) -> Result<(), String> {
+    if template.trim().is_empty() {
+        return Err("Templates can't be empty.".to_owned());
+    }
+
+    if template.chars().count() > TEMPLATE_LEN {
+        return Err(format!(
+            "Templates can be at most {TEMPLATE_LEN} characters long."
+        ));
+    }
+
+    parse(template).map(|_| ())
+}
+
+/// Fills in a template validated with [`validate`].
+///
+/// Placeholders without a value for this post render as empty text.
+pub fn render(template: &str, channel: &channels::Model, post: &Post, mention: &str) -> String {
+    let Ok(segments) = parse(template) else {
+        return template.to_owned();
+    };
+
+    segments
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Literal(text) => text,
+            Segment::Placeholder(name) => match name {
+                "mention" => mention,
+                "author" => post.author.as_deref().unwrap_or(&channel.ch_description),
+                "title" => post
+                    .title
+                    .as_deref()
+                    .or(post.text.as_deref())
+                    .unwrap_or_default(),
+                "text" => post.text.as_deref().unwrap_or_default(),
+                "url" => &post.url,
+                "channel" => &channel.ch_description,
+                "source" => post.source.as_deref().unwrap_or(&channel.ch_description),
+                "category" => post.category.as_deref().unwrap_or_default(),
+                "status" => post
+                    .live
+                    .as_ref()
+                    .map(LiveState::as_str)
+                    .unwrap_or_default(),
+                _ => "",
+            },
+        })
+        .collect()
+}
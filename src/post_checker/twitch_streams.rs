@@ -0,0 +1,529 @@
+use chrono::{DateTime, Utc};
+use entity::channels;
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use std::error::Error;
+use std::fs;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use super::{Checker, Platform, Post, Source};
+
+pub const PLATFORM: Platform = Platform {
+    name: "Twitch",
+    description: "Twitch",
+    interval: Duration::from_secs(120),
+    create: || {
+        Box::pin(async {
+            StreamChecker::new()
+                .await
+                .map(|checker| checker as Arc<dyn Checker>)
+        })
+    },
+};
+
+/// The subset of the Twitch Helix API used by [`StreamChecker`].
+#[async_trait::async_trait]
+pub trait HelixApi: Send + Sync {
+    /// Looks up a user by login name.
+    async fn user(&self, login: &str) -> Result<Option<User>, Box<dyn Error + Send + Sync>>;
+
+    /// Returns the live streams of the given user, empty when offline.
+    async fn streams(&self, login: &str) -> Result<Vec<Stream>, Box<dyn Error + Send + Sync>>;
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct User {
+    pub login: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Stream {
+    /// Unique per stream session, used for deduplication.
+    pub id: String,
+    pub user_login: String,
+    pub user_name: String,
+    pub game_name: String,
+    pub title: String,
+    pub started_at: DateTime<Utc>,
+    /// Preview URL with `{width}` and `{height}` placeholders.
+    pub thumbnail_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct HelixResponse<T> {
+    data: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TwitchClientConfig {
+    client_id: String,
+    client_secret: String,
+    #[serde(default = "default_api_url")]
+    api_url: String,
+    #[serde(default = "default_auth_url")]
+    auth_url: String,
+}
+
+fn default_api_url() -> String {
+    "https://api.twitch.tv/helix".to_owned()
+}
+
+fn default_auth_url() -> String {
+    "https://id.twitch.tv/oauth2/token".to_owned()
+}
+
+struct AppToken {
+    access_token: String,
+    expires_at: Instant,
+}
+
+/// [`HelixApi`] implementation using an app access token from the client credentials flow.
+pub struct HelixClient {
+    client: Client,
+    config: TwitchClientConfig,
+    token: RwLock<Option<AppToken>>,
+}
+
+impl HelixClient {
+    fn new(config: TwitchClientConfig) -> HelixClient {
+        Self {
+            client: Client::new(),
+            config,
+            token: RwLock::new(None),
+        }
+    }
+
+    async fn access_token(&self) -> Result<String, Box<dyn Error + Send + Sync>> {
+        if let Some(token) = self.token.read().unwrap().as_ref() {
+            if token.expires_at > Instant::now() {
+                return Ok(token.access_token.clone());
+            }
+        }
+
+        let response = self
+            .client
+            .post(&self.config.auth_url)
+            .form(&[
+                ("client_id", self.config.client_id.as_str()),
+                ("client_secret", self.config.client_secret.as_str()),
+                ("grant_type", "client_credentials"),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<TokenResponse>()
+            .await?;
+
+        // Renew a minute early so in-flight requests don't race the expiry
+        let lifetime = Duration::from_secs(response.expires_in.saturating_sub(60));
+
+        *self.token.write().unwrap() = Some(AppToken {
+            access_token: response.access_token.clone(),
+            expires_at: Instant::now() + lifetime,
+        });
+
+        Ok(response.access_token)
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Vec<T>, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/{endpoint}", self.config.api_url);
+
+        for _ in 0..2 {
+            let response = self
+                .client
+                .get(&url)
+                .query(query)
+                .header("Client-Id", &self.config.client_id)
+                .bearer_auth(self.access_token().await?)
+                .send()
+                .await?;
+
+            // The token was revoked or expired early, fetch a new one and retry once
+            if response.status() == StatusCode::UNAUTHORIZED {
+                *self.token.write().unwrap() = None;
+                continue;
+            }
+
+            let response = response
+                .error_for_status()?
+                .json::<HelixResponse<T>>()
+                .await?;
+
+            return Ok(response.data);
+        }
+
+        Err("Twitch rejected a freshly issued access token".into())
+    }
+}
+
+#[async_trait::async_trait]
+impl HelixApi for HelixClient {
+    async fn user(&self, login: &str) -> Result<Option<User>, Box<dyn Error + Send + Sync>> {
+        let users = self.get::<User>("users", &[("login", login)]).await?;
+
+        Ok(users.into_iter().next())
+    }
+
+    async fn streams(&self, login: &str) -> Result<Vec<Stream>, Box<dyn Error + Send + Sync>> {
+        self.get("streams", &[("user_login", login), ("type", "live")])
+            .await
+    }
+}
+
+pub struct StreamChecker<A = HelixClient> {
+    api: A,
+}
+
+impl StreamChecker {
+    /// Returns `None` when `keys/twitch-api.json` is missing or invalid, disabling Twitch.
+    pub async fn new() -> Option<Arc<StreamChecker>> {
+        let twitch_config = fs::read_to_string("keys/twitch-api.json")
+            .map_err(|err| err.to_string())
+            .and_then(|config| {
+                serde_json::from_str::<TwitchClientConfig>(&config).map_err(|err| err.to_string())
+            });
+
+        match twitch_config {
+            Ok(twitch_config) => Some(Arc::new(Self::with_api(HelixClient::new(twitch_config)))),
+            Err(err) => {
+                warn!("Twitch is disabled, could not load keys/twitch-api.json: {err}");
+                None
+            }
+        }
+    }
+}
+
+impl<A: HelixApi> StreamChecker<A> {
+    pub fn with_api(api: A) -> StreamChecker<A> {
+        Self { api }
+    }
+}
+
+#[async_trait::async_trait]
+impl<A: HelixApi> Checker for StreamChecker<A> {
+    async fn validate_id(&self, id: &str) -> Result<Source, String> {
+        let login = id.trim().trim_end_matches('/');
+        let login = login.rsplit('/').next().unwrap_or(login).to_lowercase();
+
+        if !(4..=25).contains(&login.len())
+            || !login.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(format!("**{id}** is not a valid Twitch username."));
+        }
+
+        match self.api.user(&login).await {
+            Ok(Some(user)) => Ok(Source {
+                id: user.login,
+                name: Some(user.display_name),
+            }),
+            Ok(None) => Err(format!("Twitch user **{login}** does not exist.")),
+            Err(err) => Err(format!("Could not look up Twitch user **{login}**: {err}")),
+        }
+    }
+
+    async fn fetch(&self, id: &str) -> Result<Vec<Post>, Box<dyn Error + Send + Sync>> {
+        let streams = self.api.streams(id).await?;
+
+        Ok(streams
+            .into_iter()
+            .map(|stream| Post {
+                url: format!("https://twitch.tv/{}", stream.user_login),
+                id: stream.id,
+                title: Some(stream.title).filter(|title| !title.is_empty()),
+                author: Some(stream.user_name),
+                category: Some(stream.game_name).filter(|game| !game.is_empty()),
+                thumbnail: Some(
+                    stream
+                        .thumbnail_url
+                        .replace("{width}", "1280")
+                        .replace("{height}", "720"),
+                ),
+                published: Some(stream.started_at),
+                ..Default::default()
+            })
+            .collect())
+    }
+
+    fn announcement(&self, channel: &channels::Model, post: &Post, mention: &str) -> String {
+        let streamer = post.author.as_ref().unwrap_or(&channel.ch_description);
+        let game = post
+            .category
+            .as_ref()
+            .map_or_else(String::new, |game| format!(" playing **{game}**"));
+        let title = post
+            .title
+            .as_ref()
+            .map_or_else(String::new, |title| format!("\n> {title}"));
+        let url = &post.url;
+
+        format!("Hey {mention}, **{streamer}** is now live{game}!{title}\n{url}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serves whatever streams the test sets as live.
+    #[derive(Default)]
+    struct FakeHelix {
+        live: Mutex<Vec<Stream>>,
+    }
+
+    impl FakeHelix {
+        fn go_live(&self, session: &str, title: &str) {
+            *self.live.lock().unwrap() = vec![Stream {
+                id: session.to_owned(),
+                user_login: "streamer".to_owned(),
+                user_name: "Streamer".to_owned(),
+                game_name: "Chess".to_owned(),
+                title: title.to_owned(),
+                started_at: Utc::now(),
+                thumbnail_url: "https://example.com/preview-{width}x{height}.jpg".to_owned(),
+            }];
+        }
+
+        fn go_offline(&self) {
+            self.live.lock().unwrap().clear();
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl HelixApi for FakeHelix {
+        async fn user(&self, login: &str) -> Result<Option<User>, Box<dyn Error + Send + Sync>> {
+            Ok((login == "streamer").then(|| User {
+                login: "streamer".to_owned(),
+                display_name: "Streamer".to_owned(),
+            }))
+        }
+
+        async fn streams(&self, login: &str) -> Result<Vec<Stream>, Box<dyn Error + Send + Sync>> {
+            let live = self.live.lock().unwrap();
+
+            Ok(live
+                .iter()
+                .filter(|stream| stream.user_login == login)
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn ids(posts: &[Post]) -> Vec<&str> {
+        posts.iter().map(|post| post.id.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn one_announcement_per_stream_session() {
+        let checker = StreamChecker::with_api(FakeHelix::default());
+        let api = &checker.api;
+
+        api.go_live("session-1", "Opening prep");
+        let first = checker.fetch("streamer").await.unwrap();
+
+        // A title change mid-stream is still the same session, so the same post
+        api.go_live("session-1", "Endgames");
+        let second = checker.fetch("streamer").await.unwrap();
+
+        assert_eq!(ids(&first), ["session-1"]);
+        assert_eq!(ids(&second), ["session-1"]);
+        assert_eq!(second[0].title.as_deref(), Some("Endgames"));
+        assert_eq!(
+            second[0].thumbnail.as_deref(),
+            Some("https://example.com/preview-1280x720.jpg")
+        );
+    }
+
+    #[tokio::test]
+    async fn offline_to_online_is_a_new_post() {
+        let checker = StreamChecker::with_api(FakeHelix::default());
+        let api = &checker.api;
+
+        assert!(checker.fetch("streamer").await.unwrap().is_empty());
+
+        api.go_live("session-1", "Opening prep");
+        assert_eq!(
+            ids(&checker.fetch("streamer").await.unwrap()),
+            ["session-1"]
+        );
+
+        api.go_offline();
+        assert!(checker.fetch("streamer").await.unwrap().is_empty());
+
+        // Going live again starts a new session, which is announced again
+        api.go_live("session-2", "Rematch");
+        assert_eq!(
+            ids(&checker.fetch("streamer").await.unwrap()),
+            ["session-2"]
+        );
+    }
+
+    #[tokio::test]
+    async fn validates_logins_through_the_api() {
+        let checker = StreamChecker::with_api(FakeHelix::default());
+
+        let source = checker
+            .validate_id("https://twitch.tv/Streamer/")
+            .await
+            .unwrap();
+
+        assert_eq!(source.id, "streamer");
+        assert_eq!(source.name.as_deref(), Some("Streamer"));
+        assert!(checker.validate_id("nobody").await.is_err());
+        assert!(checker.validate_id("no").await.is_err());
+    }
+
+    const STREAMS: &str = r#"{
+        "data": [
+            {
+                "id": "40952121085",
+                "user_login": "streamer",
+                "user_name": "Streamer",
+                "game_name": "Chess",
+                "title": "Opening prep",
+                "started_at": "2026-10-17T12:00:00Z",
+                "thumbnail_url": "https://example.com/preview-{width}x{height}.jpg"
+            }
+        ],
+        "pagination": {}
+    }"#;
+
+    /// Serves a token endpoint and the Helix API on a local port, returns the config to reach it.
+    ///
+    /// The first token issued is treated as revoked, and `issued` counts the tokens handed out.
+    async fn helix_server(issued: Arc<AtomicUsize>) -> TwitchClientConfig {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                let head_len = loop {
+                    if let Some(end) = request.windows(4).position(|window| window == b"\r\n\r\n") {
+                        break end + 4;
+                    }
+                    let read = socket.read(&mut buf).await.unwrap();
+                    if read == 0 {
+                        break request.len();
+                    }
+                    request.extend_from_slice(&buf[..read]);
+                };
+
+                let head = String::from_utf8_lossy(&request[..head_len]).to_lowercase();
+                let header = |name: &str| {
+                    head.lines()
+                        .find_map(|line| line.strip_prefix(&format!("{name}: ")))
+                        .map(str::to_owned)
+                };
+
+                // Read the form body of token requests before answering
+                let body_len = header("content-length").map_or(0, |len| len.parse().unwrap());
+                while request.len() < head_len + body_len {
+                    let read = socket.read(&mut buf).await.unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..read]);
+                }
+
+                let target = head.split(' ').nth(1).unwrap_or_default().to_owned();
+                let authorized = header("authorization")
+                    .filter(|token| token != "bearer token-1")
+                    .is_some();
+
+                let (status, body) = if target == "/token" {
+                    let token = issued.fetch_add(1, Ordering::SeqCst) + 1;
+                    (
+                        "200 OK",
+                        format!(r#"{{"access_token":"token-{token}","expires_in":3600}}"#),
+                    )
+                } else if header("client-id").as_deref() != Some("client") {
+                    ("400 Bad Request", String::new())
+                } else if !authorized {
+                    ("401 Unauthorized", String::new())
+                } else if target.starts_with("/helix/users?login=streamer") {
+                    (
+                        "200 OK",
+                        r#"{"data":[{"id":"1","login":"streamer","display_name":"Streamer"}]}"#
+                            .to_owned(),
+                    )
+                } else if target.starts_with("/helix/users") {
+                    ("200 OK", r#"{"data":[]}"#.to_owned())
+                } else if target.starts_with("/helix/streams?user_login=streamer") {
+                    ("200 OK", STREAMS.to_owned())
+                } else if target.starts_with("/helix/streams") {
+                    ("200 OK", r#"{"data":[],"pagination":{}}"#.to_owned())
+                } else {
+                    ("404 Not Found", String::new())
+                };
+
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        TwitchClientConfig {
+            client_id: "client".to_owned(),
+            client_secret: "secret".to_owned(),
+            api_url: format!("http://{address}/helix"),
+            auth_url: format!("http://{address}/token"),
+        }
+    }
+
+    #[tokio::test]
+    async fn refreshes_a_rejected_token_once() {
+        let issued = Arc::new(AtomicUsize::new(0));
+        let client = HelixClient::new(helix_server(issued.clone()).await);
+
+        let user = client.user("streamer").await.unwrap().unwrap();
+
+        assert_eq!(user.login, "streamer");
+        assert_eq!(user.display_name, "Streamer");
+        assert_eq!(issued.load(Ordering::SeqCst), 2);
+
+        // The refreshed token is kept for later requests
+        assert!(client.user("nobody").await.unwrap().is_none());
+        assert_eq!(issued.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn decodes_helix_streams() {
+        let issued = Arc::new(AtomicUsize::new(0));
+        let checker = StreamChecker::with_api(HelixClient::new(helix_server(issued.clone()).await));
+
+        let posts = checker.fetch("streamer").await.unwrap();
+
+        assert_eq!(ids(&posts), ["40952121085"]);
+        assert_eq!(posts[0].url, "https://twitch.tv/streamer");
+        assert_eq!(posts[0].title.as_deref(), Some("Opening prep"));
+        assert_eq!(posts[0].author.as_deref(), Some("Streamer"));
+        assert_eq!(posts[0].category.as_deref(), Some("Chess"));
+        assert_eq!(
+            posts[0].published,
+            Some("2026-10-17T12:00:00Z".parse().unwrap())
+        );
+        assert!(checker.fetch("offline").await.unwrap().is_empty());
+    }
+}
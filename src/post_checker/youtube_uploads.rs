@@ -1,26 +1,106 @@
-use entity::{channels, platforms, posts};
+use chrono::{DateTime, Utc};
+use entity::channels;
 use google_youtube3::hyper::client::HttpConnector;
 use google_youtube3::hyper_rustls::HttpsConnector;
 use google_youtube3::{hyper, hyper_rustls, oauth2, YouTube};
-use poise::serenity_prelude::{ChannelId, Mentionable, ParseValue, RoleId};
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use poise::serenity_prelude::CreateEmbed;
+use reqwest::header::COOKIE;
+use reqwest::redirect::Policy;
+use reqwest::{Client, StatusCode};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::env;
 use std::error::Error;
 use std::fs;
-use std::sync::Arc;
-use tracing::{error, info};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::warn;
 
-use crate::commands::PlatformType;
+use super::http::HttpCache;
+use super::quota::Quota;
+use super::{base_embed, fetch_rss, Checker, LiveState, Platform, Post, Source, VideoKind};
 
-use super::Checker;
+pub const PLATFORM: Platform = Platform {
+    name: "YouTube",
+    description: "YouTube",
+    interval: Duration::from_secs(300),
+    create: || Box::pin(async { Some(UploadChecker::new().await as Arc<dyn Checker>) }),
+};
+
+/// Longest video YouTube accepts as a Short.
+const MAX_SHORT_LEN: Duration = Duration::from_secs(180);
+
+/// Bound on the video details cache, cleared whenever it fills up.
+const DETAILS_CACHE_LEN: usize = 4096;
+
+/// Daily Data API units spent unless `YOUTUBE_QUOTA_BUDGET` says otherwise, leaves some
+/// of the default 10,000 unit quota for estimation errors.
+const DEFAULT_QUOTA_BUDGET: u32 = 9000;
+
+/// Cost of the `list` calls the checker makes, in quota units.
+const LIST_COST: u32 = 1;
+
+/// Most video IDs a single `videos().list` call accepts.
+const MAX_LIST_IDS: usize = 50;
+
+type Hub = YouTube<HttpsConnector<HttpConnector>>;
+
+/// Details of a video, only broadcasts change once they are looked up.
+#[derive(Debug, Clone, Copy, Default)]
+struct VideoDetails {
+    kind: VideoKind,
+    duration: Option<Duration>,
+    live: Option<LiveState>,
+    scheduled: Option<DateTime<Utc>>,
+}
 
 pub struct UploadChecker {
-    hub: YouTube<HttpsConnector<HttpConnector>>,
-    debug_mode: bool,
-    db: DatabaseConnection,
+    /// Data API client, `None` when the deployment uses the public Atom feeds.
+    hub: Option<Hub>,
+    /// Data API units spent today, the feeds are used instead once they run out.
+    quota: Quota,
+    client: Client,
+    cache: HttpCache,
+    /// Client that doesn't follow redirects, for telling Shorts apart.
+    shorts_client: Client,
+    details: RwLock<HashMap<String, VideoDetails>>,
 }
 
 impl UploadChecker {
-    pub async fn new(debug_mode: bool, connection: DatabaseConnection) -> Arc<UploadChecker> {
+    pub async fn new() -> Arc<UploadChecker> {
+        let use_api = env::var("YOUTUBE_BACKEND")
+            .map(|s| !s.eq_ignore_ascii_case("feed"))
+            .unwrap_or(true);
+
+        let hub = if use_api {
+            Some(Self::api_hub().await)
+        } else {
+            None
+        };
+
+        let budget = env::var("YOUTUBE_QUOTA_BUDGET")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_QUOTA_BUDGET);
+
+        let client = reqwest::Client::builder().https_only(true).build().unwrap();
+        let shorts_client = reqwest::Client::builder()
+            .https_only(true)
+            .redirect(Policy::none())
+            .build()
+            .unwrap();
+
+        Arc::new(Self {
+            hub,
+            quota: Quota::new(budget),
+            client,
+            cache: HttpCache::default(),
+            shorts_client,
+            details: RwLock::new(HashMap::new()),
+        })
+    }
+
+    async fn api_hub() -> Hub {
         let connector = hyper_rustls::HttpsConnectorBuilder::new()
             .with_native_roots()
             .https_only()
@@ -40,117 +120,740 @@ impl UploadChecker {
             .await
             .unwrap();
 
-        Arc::new(Self {
-            hub: YouTube::new(youtube_client, auth),
-            debug_mode,
-            db: connection,
-        })
+        YouTube::new(youtube_client, auth)
     }
-}
 
-#[async_trait::async_trait]
-impl Checker for UploadChecker {
-    fn name(&self) -> &str {
-        "YouTube"
+    /// The Data API client if there is quota left for a `list` call, books the call.
+    fn api(&self) -> Option<&Hub> {
+        self.hub
+            .as_ref()
+            .filter(|_| self.quota.try_spend(LIST_COST))
     }
 
-    async fn check(
+    /// Notes when the Data API reports the quota used up, so the feeds take over.
+    fn api_error(&self, err: google_youtube3::Error) -> Box<dyn Error + Send + Sync> {
+        if let google_youtube3::Error::BadRequest(value) = &err {
+            let exceeded = value["error"]["errors"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .any(|error| {
+                    matches!(
+                        error["reason"].as_str(),
+                        Some("quotaExceeded" | "dailyLimitExceeded")
+                    )
+                });
+
+            if exceeded {
+                warn!("YouTube Data API quota exceeded, using the feeds until it resets");
+                self.quota.exhaust();
+            }
+        }
+
+        err.into()
+    }
+
+    async fn fetch_api(
         &self,
-        ctx: impl AsRef<serenity::http::Http> + Send + Sync,
-    ) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let platform_channels = platforms::Entity::find()
-            .filter(platforms::Column::PlName.eq(PlatformType::YouTube.to_string()))
-            .find_with_related(channels::Entity)
-            .all(&self.db)
-            .await?
+        hub: &Hub,
+        id: &str,
+    ) -> Result<Vec<Post>, Box<dyn Error + Send + Sync>> {
+        let (_, response) = hub
+            .playlist_items()
+            .list(&vec!["snippet".to_string(), "contentDetails".to_string()])
+            .playlist_id(id)
+            .doit()
+            .await
+            .map_err(|err| self.api_error(err))?;
+
+        Ok(response
+            .items
+            .unwrap_or_default()
             .into_iter()
-            .flat_map(|(_, r)| r)
-            .collect::<Vec<_>>();
+            .filter_map(|item| {
+                let details = item.content_details?;
+                let video_id = details.video_id?;
+                let snippet = item.snippet.unwrap_or_default();
 
-        for channel in platform_channels {
-            let result = self
-                .hub
-                .playlist_items()
-                .list(&vec!["contentDetails".to_string()])
-                .playlist_id(&channel.ch_name)
-                .doit()
-                .await;
-
-            if let Err(ref err) = result {
-                error!("YouTube API error: {:?}", err);
-                continue;
+                let thumbnail = snippet.thumbnails.and_then(|thumbnails| {
+                    thumbnails
+                        .maxres
+                        .or(thumbnails.standard)
+                        .or(thumbnails.high)
+                        .or(thumbnails.medium)
+                        .or(thumbnails.default)?
+                        .url
+                });
+
+                Some(Post {
+                    url: format!("https://youtube.com/watch?v={video_id}"),
+                    id: video_id,
+                    title: snippet.title,
+                    author: snippet.video_owner_channel_title,
+                    thumbnail,
+                    published: details
+                        .video_published_at
+                        .as_deref()
+                        .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+                        .map(|date| date.with_timezone(&Utc)),
+                    ..Default::default()
+                })
+            })
+            .collect())
+    }
+
+    async fn fetch_feed(&self, id: &str) -> Result<Vec<Post>, Box<dyn Error + Send + Sync>> {
+        let feed = fetch_rss(&self.client, &self.cache, Cow::Owned(feed_url(id))).await?;
+
+        Ok(feed
+            .entries
+            .into_iter()
+            .filter_map(|entry| {
+                // Keep IDs identical to the Data API so switching backends doesn't repost
+                let video_id = entry.id.strip_prefix("yt:video:")?.to_owned();
+
+                let thumbnail = entry
+                    .media
+                    .into_iter()
+                    .flat_map(|media| media.thumbnails)
+                    .next()
+                    .map(|thumbnail| thumbnail.image.uri);
+
+                Some(Post {
+                    url: format!("https://youtube.com/watch?v={video_id}"),
+                    id: video_id,
+                    title: entry.title.map(|title| title.content),
+                    author: entry.authors.into_iter().next().map(|author| author.name),
+                    thumbnail,
+                    published: entry.published,
+                    ..Default::default()
+                })
+            })
+            .collect())
+    }
+
+    /// Whether a video is a Short, YouTube redirects `/shorts/` URLs of other videos.
+    ///
+    /// A failed lookup counts the video as a regular one rather than failing the check.
+    async fn is_short(&self, video_id: &str) -> bool {
+        let response = self
+            .shorts_client
+            .head(format!("https://www.youtube.com/shorts/{video_id}"))
+            .header(COOKIE, "SOCS=CAI")
+            .send()
+            .await;
+
+        match response {
+            Ok(response) => response.status().is_success(),
+            Err(err) => {
+                warn!("Could not tell whether {video_id} is a Short: {err}");
+                false
             }
+        }
+    }
+
+    /// Which of at most `MAX_LIST_IDS` videos were deleted or made private, per the Data API.
+    async fn removed_api(
+        &self,
+        hub: &Hub,
+        ids: &[String],
+    ) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let mut request = hub.videos().list(&vec!["status".to_string()]);
 
-            let (_, response) = result.unwrap();
+        for video_id in ids {
+            request = request.add_id(video_id);
+        }
 
-            let Some(items) = &response.items else { continue; };
+        let (_, response) = request.doit().await.map_err(|err| self.api_error(err))?;
 
-            for item in items {
-                let id_opt = item
-                    .content_details
+        // Deleted videos are left out of the response, private ones are listed as such
+        let public = response
+            .items
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|video| {
+                video
+                    .status
                     .as_ref()
-                    .and_then(|d| d.video_id.as_ref());
+                    .and_then(|status| status.privacy_status.as_deref())
+                    != Some("private")
+            })
+            .filter_map(|video| video.id)
+            .collect::<Vec<_>>();
 
-                if id_opt.is_none() {
-                    continue;
-                }
+        Ok(ids
+            .iter()
+            .filter(|id| !public.contains(id))
+            .cloned()
+            .collect())
+    }
 
-                let id = id_opt.unwrap();
+    /// Which videos were deleted, per oEmbed, this costs no quota.
+    ///
+    /// oEmbed also refuses public videos that can't be embedded, only a 404 counts as removed.
+    async fn removed_oembed(
+        &self,
+        ids: &[String],
+    ) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let mut removed = Vec::new();
 
-                let matches = posts::Entity::find()
-                    .filter(posts::Column::PoName.eq(id.clone()))
-                    .one(&self.db)
-                    .await;
+        for video_id in ids {
+            let status = self
+                .client
+                .get("https://www.youtube.com/oembed")
+                .query(&[
+                    ("url", format!("https://www.youtube.com/watch?v={video_id}")),
+                    ("format", "json".to_owned()),
+                ])
+                .send()
+                .await?
+                .status();
 
-                if let Err(ref err) = matches {
-                    error!("DB error checking for matches: {err}");
-                    continue;
-                }
+            if status == StatusCode::NOT_FOUND {
+                removed.push(video_id.clone());
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Looks up the type and duration of videos not seen before.
+    ///
+    /// Premieres and livestreams are only told apart with the Data API, with the feeds or
+    /// once the quota is used up every video that isn't a Short counts as a regular video.
+    /// Broadcasts that haven't ended are looked up on every check to follow their state.
+    async fn classify(&self, posts: &mut [Post]) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let pending = {
+            let details = self.details.read().unwrap();
+
+            posts
+                .iter()
+                .filter(|post| {
+                    details.get(&post.id).is_none_or(|video| {
+                        matches!(video.live, Some(LiveState::Upcoming | LiveState::Live))
+                    })
+                })
+                .map(|post| post.id.clone())
+                .collect::<Vec<_>>()
+        };
 
-                if let Ok(Some(_)) = matches {
+        let mut found = HashMap::new();
+
+        let hub = if pending.is_empty() { None } else { self.api() };
+
+        if let Some(hub) = hub {
+            let mut request = hub.videos().list(&vec![
+                "snippet".to_string(),
+                "contentDetails".to_string(),
+                "liveStreamingDetails".to_string(),
+            ]);
+
+            for video_id in &pending {
+                request = request.add_id(video_id);
+            }
+
+            let (_, response) = request.doit().await.map_err(|err| self.api_error(err))?;
+
+            for video in response.items.unwrap_or_default() {
+                let Some(video_id) = video.id else {
                     continue;
-                }
+                };
 
-                info!("New post: {}, debug mode: {}", id, self.debug_mode);
+                // Upcoming and running livestreams report a zero duration
+                let duration = video
+                    .content_details
+                    .and_then(|details| details.duration)
+                    .and_then(|duration| parse_duration(&duration))
+                    .filter(|duration| !duration.is_zero());
 
-                let post = posts::ActiveModel {
-                    po_ch_id: Set(channel.ch_id),
-                    po_name: Set(id.to_owned()),
-                    po_time_added: Set(chrono::Utc::now().naive_utc()),
-                    ..Default::default()
+                let broadcast = video
+                    .snippet
+                    .and_then(|snippet| snippet.live_broadcast_content);
+
+                let streaming = video.live_streaming_details;
+
+                let scheduled = streaming
+                    .as_ref()
+                    .and_then(|details| details.scheduled_start_time.as_deref())
+                    .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+                    .map(|date| date.with_timezone(&Utc));
+
+                let live = match broadcast.as_deref() {
+                    Some("upcoming") => Some(LiveState::Upcoming),
+                    Some("live") => Some(LiveState::Live),
+                    _ if streaming.is_some() => Some(LiveState::Ended),
+                    _ => None,
                 };
 
-                post.save(&self.db).await?;
+                // A broadcast keeps its type once known, an aired premiere looks like
+                // an ended livestream
+                let known = self
+                    .details
+                    .read()
+                    .unwrap()
+                    .get(&video_id)
+                    .map(|video| video.kind);
+
+                let kind = match (known, live) {
+                    (Some(kind), _) => kind,
+                    // Premieres are uploaded ahead of time and already have a duration
+                    (None, Some(LiveState::Upcoming | LiveState::Live)) if duration.is_some() => {
+                        VideoKind::Premiere
+                    }
+                    (None, Some(_)) => VideoKind::Stream,
+                    (None, None)
+                        if duration.is_some_and(|d| d <= MAX_SHORT_LEN)
+                            && self.is_short(&video_id).await =>
+                    {
+                        VideoKind::Short
+                    }
+                    (None, None) => VideoKind::Video,
+                };
 
-                let channel_name = &channel.ch_description;
-                let mention = if let Some(role) = channel.ch_role_mention_id {
-                    RoleId::from(role as u64).mention().to_string()
+                found.insert(
+                    video_id,
+                    VideoDetails {
+                        kind,
+                        duration,
+                        live,
+                        scheduled,
+                    },
+                );
+            }
+        } else {
+            // The feeds can't follow a broadcast, keep what the Data API last reported
+            let known = {
+                let details = self.details.read().unwrap();
+
+                pending
+                    .iter()
+                    .filter(|video_id| details.contains_key(*video_id))
+                    .cloned()
+                    .collect::<HashSet<_>>()
+            };
+
+            for video_id in pending {
+                if known.contains(&video_id) {
+                    continue;
+                }
+
+                let kind = if self.is_short(&video_id).await {
+                    VideoKind::Short
                 } else {
-                    "@everyone".to_owned()
+                    VideoKind::Video
                 };
 
-                let text = format!(
-                        "Hey {mention}, **{channel_name}** has released a new video!\nhttps://youtube.com/watch?v={id}"
-                    );
-
-                ChannelId::from(channel.ch_discord_channel_id as u64)
-                    .send_message(&ctx, |msg| {
-                        if !self.debug_mode && channel.ch_mention_flag {
-                            msg.content(text).allowed_mentions(|am| {
-                                if let Some(role_id) = channel.ch_role_mention_id {
-                                    am.empty_parse().roles(vec![role_id as u64])
-                                } else {
-                                    am.empty_parse().parse(ParseValue::Everyone)
-                                }
-                            })
-                        } else {
-                            msg.content(text).allowed_mentions(|am| am.empty_parse())
-                        }
-                    })
-                    .await?;
+                found.insert(
+                    video_id,
+                    VideoDetails {
+                        kind,
+                        ..Default::default()
+                    },
+                );
             }
         }
 
+        let mut details = self.details.write().unwrap();
+
+        if details.len() + found.len() > DETAILS_CACHE_LEN {
+            details.clear();
+        }
+
+        details.extend(found);
+
+        for post in posts.iter_mut() {
+            let Some(video) = details.get(&post.id) else {
+                continue;
+            };
+
+            post.video = Some(video.kind);
+            post.duration = video.duration;
+            post.live = video.live;
+            post.scheduled = video.scheduled;
+        }
+
         Ok(())
     }
+
+    /// Looks up the channel ID of a handle or custom URL from its channel page.
+    ///
+    /// Neither the Data API nor the feeds can resolve `/c/` URLs, and this costs no quota.
+    async fn channel_id_from_page(
+        &self,
+        path: &str,
+    ) -> Result<String, Box<dyn Error + Send + Sync>> {
+        const CANONICAL: &str = "<link rel=\"canonical\" href=\"https://www.youtube.com/channel/";
+
+        let page = self
+            .client
+            .get(format!("https://www.youtube.com/{path}"))
+            // Skip the EU cookie consent interstitial
+            .header(COOKIE, "SOCS=CAI")
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let start = page
+            .find(CANONICAL)
+            .ok_or("channel page has no channel ID")?
+            + CANONICAL.len();
+
+        Ok(page[start..]
+            .chars()
+            .take_while(|&c| is_id_char(c))
+            .collect())
+    }
+
+    /// Looks up the profile picture of a channel from its channel page, this costs no quota.
+    async fn channel_icon(
+        &self,
+        channel_id: &str,
+    ) -> Result<Option<String>, Box<dyn Error + Send + Sync>> {
+        const OG_IMAGE: &str = "<meta property=\"og:image\" content=\"";
+
+        let page = self
+            .client
+            .get(format!("https://www.youtube.com/channel/{channel_id}"))
+            // Skip the EU cookie consent interstitial
+            .header(COOKIE, "SOCS=CAI")
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        Ok(page.find(OG_IMAGE).map(|start| {
+            page[start + OG_IMAGE.len()..]
+                .chars()
+                .take_while(|&c| c != '"')
+                .collect()
+        }))
+    }
+
+    /// Resolves a `UC…` channel ID to its uploads playlist.
+    async fn resolve_channel(
+        &self,
+        channel_id: &str,
+    ) -> Result<Source, Box<dyn Error + Send + Sync>> {
+        let Some(hub) = self.api() else {
+            let uploads = format!(
+                "UU{}",
+                channel_id.strip_prefix("UC").ok_or("not a channel ID")?
+            );
+            let feed = fetch_rss(&self.client, &self.cache, Cow::Owned(feed_url(&uploads))).await?;
+
+            return Ok(Source {
+                id: uploads,
+                name: feed.title.map(|title| title.content),
+            });
+        };
+
+        let (_, response) = hub
+            .channels()
+            .list(&vec!["snippet".to_string(), "contentDetails".to_string()])
+            .add_id(channel_id)
+            .doit()
+            .await
+            .map_err(|err| self.api_error(err))?;
+
+        let channel = response
+            .items
+            .and_then(|items| items.into_iter().next())
+            .ok_or("no such channel")?;
+
+        let uploads = channel
+            .content_details
+            .and_then(|details| details.related_playlists?.uploads)
+            .ok_or("channel has no uploads playlist")?;
+
+        Ok(Source {
+            id: uploads,
+            name: channel.snippet.and_then(|snippet| snippet.title),
+        })
+    }
+
+    /// Checks that a playlist exists and finds the name of the channel owning it.
+    async fn resolve_playlist(
+        &self,
+        playlist_id: &str,
+    ) -> Result<Source, Box<dyn Error + Send + Sync>> {
+        let Some(hub) = self.api() else {
+            let feed =
+                fetch_rss(&self.client, &self.cache, Cow::Owned(feed_url(playlist_id))).await?;
+
+            return Ok(Source {
+                id: playlist_id.to_owned(),
+                name: feed.authors.into_iter().next().map(|author| author.name),
+            });
+        };
+
+        let (_, response) = hub
+            .playlists()
+            .list(&vec!["snippet".to_string()])
+            .add_id(playlist_id)
+            .doit()
+            .await
+            .map_err(|err| self.api_error(err))?;
+
+        let playlist = response
+            .items
+            .and_then(|items| items.into_iter().next())
+            .ok_or("no such playlist")?;
+
+        Ok(Source {
+            id: playlist_id.to_owned(),
+            name: playlist.snippet.and_then(|snippet| snippet.channel_title),
+        })
+    }
+}
+
+/// What a user may paste into `add_channel` to identify a YouTube channel.
+#[derive(Debug, PartialEq, Eq)]
+enum ChannelRef {
+    /// An `@handle`, `c/name` or `user/name` path, only resolvable through the channel page.
+    Page(String),
+    /// A `UC…` channel ID.
+    Channel(String),
+    /// An uploads (`UU…`) or regular playlist ID.
+    Playlist(String),
+}
+
+impl ChannelRef {
+    fn parse(input: &str) -> Option<ChannelRef> {
+        let input = input.trim();
+        let rest = input
+            .strip_prefix("https://")
+            .or_else(|| input.strip_prefix("http://"))
+            .unwrap_or(input);
+
+        let (is_url, path) = match rest.split_once('/') {
+            Some((host, path)) if host == "youtube.com" || host.ends_with(".youtube.com") => {
+                (true, path)
+            }
+            _ => (false, rest),
+        };
+
+        if let Some(query) = path.strip_prefix("playlist?") {
+            return query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("list="))
+                .filter(|id| is_id(id))
+                .map(|id| ChannelRef::Playlist(id.to_owned()));
+        }
+
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        let mut segments = path.split('/').filter(|s| !s.is_empty());
+
+        match (segments.next()?, segments.next()) {
+            (handle, _) if handle.starts_with('@') => {
+                let valid = handle[1..].chars().all(|c| is_id_char(c) || c == '.');
+                valid.then(|| ChannelRef::Page(handle.to_owned()))
+            }
+            ("channel", Some(id)) if is_id(id) => Some(ChannelRef::Channel(id.to_owned())),
+            (kind @ ("c" | "user"), Some(name)) if is_id(name) => {
+                Some(ChannelRef::Page(format!("{kind}/{name}")))
+            }
+            (id, None) if !is_url && id.starts_with("UC") && id.len() == 24 && is_id(id) => {
+                Some(ChannelRef::Channel(id.to_owned()))
+            }
+            (id, None) if !is_url && id.len() <= 48 && is_id(id) => {
+                Some(ChannelRef::Playlist(id.to_owned()))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+fn is_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(is_id_char)
+}
+
+/// Parses an ISO 8601 duration as used by the Data API, e.g. `PT1H2M3S`.
+fn parse_duration(duration: &str) -> Option<Duration> {
+    let (days, time) = duration
+        .strip_prefix('P')?
+        .split_once('T')
+        .unwrap_or((duration.strip_prefix('P')?, ""));
+
+    let mut secs = 0;
+
+    for (part, units) in [
+        (days, [('D', 86400)].as_slice()),
+        (time, &[('H', 3600), ('M', 60), ('S', 1)]),
+    ] {
+        let mut rest = part;
+
+        for &(unit, factor) in units {
+            if let Some((value, tail)) = rest.split_once(unit) {
+                secs += value.parse::<u64>().ok()? * factor;
+                rest = tail;
+            }
+        }
+
+        if !rest.is_empty() {
+            return None;
+        }
+    }
+
+    Some(Duration::from_secs(secs))
+}
+
+/// Formats a video length the way YouTube shows it, e.g. `1:02:03`.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// Public Atom feed of a playlist.
+fn feed_url(playlist_id: &str) -> String {
+    // Uploads playlists mirror the channel ID, `UU…` belongs to channel `UC…`
+    match playlist_id.strip_prefix("UU") {
+        Some(rest) => format!("https://www.youtube.com/feeds/videos.xml?channel_id=UC{rest}"),
+        None => format!("https://www.youtube.com/feeds/videos.xml?playlist_id={playlist_id}"),
+    }
+}
+
+#[async_trait::async_trait]
+impl Checker for UploadChecker {
+    async fn validate_id(&self, id: &str) -> Result<Source, String> {
+        let Some(channel_ref) = ChannelRef::parse(id) else {
+            return Err(format!(
+                "**{id}** is not a YouTube channel URL, handle, channel ID or playlist ID."
+            ));
+        };
+
+        let res = match channel_ref {
+            ChannelRef::Page(path) => match self.channel_id_from_page(&path).await {
+                Ok(channel_id) => self.resolve_channel(&channel_id).await,
+                Err(err) => Err(err),
+            },
+            ChannelRef::Channel(channel_id) => self.resolve_channel(&channel_id).await,
+            ChannelRef::Playlist(playlist_id) => self.resolve_playlist(&playlist_id).await,
+        };
+
+        res.map_err(|err| format!("Could not find YouTube channel **{id}**: {err}"))
+    }
+
+    async fn fetch(&self, id: &str) -> Result<Vec<Post>, Box<dyn Error + Send + Sync>> {
+        let mut posts = match self.api() {
+            Some(hub) => match self.fetch_api(hub, id).await {
+                Err(_) if self.quota.is_exhausted() => self.fetch_feed(id).await?,
+                res => res?,
+            },
+            None => self.fetch_feed(id).await?,
+        };
+
+        self.classify(&mut posts).await?;
+
+        Ok(posts)
+    }
+
+    async fn removed(&self, ids: &[String]) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let mut removed = Vec::new();
+
+        for chunk in ids.chunks(MAX_LIST_IDS) {
+            let found = match self.api() {
+                Some(hub) => self.removed_api(hub, chunk).await?,
+                None => self.removed_oembed(chunk).await?,
+            };
+
+            removed.extend(found);
+        }
+
+        Ok(removed)
+    }
+
+    async fn avatar(&self, id: &str) -> Result<Option<String>, Box<dyn Error + Send + Sync>> {
+        // Only uploads playlists tell which channel they belong to from their ID
+        match id.strip_prefix("UU") {
+            Some(rest) => self.channel_icon(&format!("UC{rest}")).await,
+            None => Ok(None),
+        }
+    }
+
+    fn pace(&self, interval: Duration) -> Duration {
+        match self.hub {
+            Some(_) => self.quota.pace(interval),
+            None => interval,
+        }
+    }
+
+    fn status(&self) -> Option<String> {
+        if self.hub.is_none() {
+            return Some("Reading the public feeds.".to_owned());
+        }
+
+        let usage = format!(
+            "{} of {} Data API units spent today, resets <t:{}:R>.",
+            self.quota.spent(),
+            self.quota.budget(),
+            self.quota.resets_at().timestamp()
+        );
+
+        if self.quota.is_exhausted() {
+            Some(format!(
+                "{usage}\nQuota used up, reading the public feeds until it resets."
+            ))
+        } else {
+            Some(usage)
+        }
+    }
+
+    fn announcement(&self, channel: &channels::Model, post: &Post, mention: &str) -> String {
+        let channel_name = &channel.ch_description;
+        let action = match (post.video, post.live) {
+            (Some(VideoKind::Short), _) => "has released a new Short",
+            (Some(VideoKind::Premiere), Some(LiveState::Upcoming)) => "has scheduled a premiere",
+            (Some(VideoKind::Premiere), Some(LiveState::Live)) => "is premiering a new video",
+            (Some(VideoKind::Premiere), _) => "has premiered a new video",
+            (Some(VideoKind::Stream), Some(LiveState::Upcoming)) => "has scheduled a livestream",
+            (Some(VideoKind::Stream), Some(LiveState::Live)) => "is live",
+            (Some(VideoKind::Stream), _) => "was live",
+            (Some(VideoKind::Video) | None, _) => "has released a new video",
+        };
+        let scheduled = match (post.live, post.scheduled) {
+            (Some(LiveState::Upcoming), Some(scheduled)) => {
+                format!(", starting <t:{}:R>", scheduled.timestamp())
+            }
+            _ => String::new(),
+        };
+        let url = &post.url;
+
+        format!("Hey {mention}, **{channel_name}** {action}{scheduled}!\n{url}")
+    }
+
+    fn embed(&self, channel: &channels::Model, post: &Post, embed: &mut CreateEmbed) {
+        base_embed(channel, post, embed);
+
+        embed.author(|a| a.name(post.author.as_ref().unwrap_or(&channel.ch_description)));
+
+        match (post.live, post.scheduled) {
+            (Some(LiveState::Upcoming), Some(scheduled)) => {
+                embed.field("Starts", format!("<t:{}:R>", scheduled.timestamp()), true);
+            }
+            (Some(LiveState::Live), _) => {
+                embed.field("Status", "Live now", true);
+            }
+            (Some(LiveState::Ended), _) => {
+                embed.field("Status", "Ended", true);
+            }
+            _ => {}
+        }
+
+        if let Some(duration) = post.duration {
+            embed.field("Duration", format_duration(duration), true);
+        }
+    }
 }